@@ -2,20 +2,241 @@ pub mod program_error;
 pub mod regex;
 
 use program_error::ProgramError;
-use regex::Regex;
+use regex::regex_error::RegexError;
+use regex::regex_options::RegexOptions;
+use regex::{escape, Regex};
 
-use std::error::Error;
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::fs;
-use std::io::Write;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-#[derive(Debug)]
+const FLAGS: [&str; 56] = [
+    "-z",
+    "--null",
+    "-a",
+    "--text",
+    "--multiline",
+    "-s",
+    "--dotall",
+    "-r",
+    "--recursive",
+    "-S",
+    "--follow-symlinks",
+    "--stats",
+    "--max-depth",
+    "--line-buffered",
+    "--progress",
+    "--color",
+    "-c",
+    "--count",
+    "-n",
+    "--line-number",
+    "--field-separator",
+    "--warn-empty",
+    "-v",
+    "--invert-match",
+    "-F",
+    "--fixed-strings",
+    "-i",
+    "--ignore-case",
+    "-h",
+    "--no-filename",
+    "-H",
+    "--with-filename",
+    "--unique",
+    "--unique-global",
+    "-o",
+    "--only-matching",
+    "-l",
+    "--files-with-matches",
+    "--encoding",
+    "-e",
+    "--regexp",
+    "-f",
+    "--pattern-file",
+    "--ignore-bad-patterns",
+    "-C",
+    "--context",
+    "--tsv",
+    "--sort",
+    "--column",
+    "--match-filename",
+    "--max-line-length",
+    "--record-separator",
+    "--max-count-per-file",
+    "--files-from",
+    "--help",
+    "--version",
+];
+const BINARY_CHECK_SIZE: usize = 8192;
+const COLOR_MATCH: &str = "\x1b[01;31m";
+const COLOR_FILENAME: &str = "\x1b[35m";
+const COLOR_LINE_NUMBER: &str = "\x1b[32m";
+const COLOR_RESET: &str = "\x1b[0m";
+const STDIN_PATH: &str = "-";
+const PROGRESS_INTERVAL: usize = 10000;
+pub const EXIT_MATCH: i32 = 0;
+pub const EXIT_NO_MATCH: i32 = 1;
+pub const EXIT_ERROR: i32 = 2;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    #[default]
+    Never,
+    Always,
+    Auto,
+}
+
+/// Escape codes of the colors of each field of a printed line.
+/// By default, as in grep, the file name is magenta, the line number and column are green
+/// and the matches are red.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorScheme {
+    pub filename: &'static str,
+    pub line_number: &'static str,
+    pub matched: &'static str,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme {
+            filename: COLOR_FILENAME,
+            line_number: COLOR_LINE_NUMBER,
+            matched: COLOR_MATCH,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SortMode {
+    #[default]
+    Unsorted,
+    Alphabetic,
+    Numeric,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Latin1,
+}
+
+#[derive(Debug, Default)]
 pub struct Arguments {
     pub regex: String,
+    pub patterns: Vec<String>,
+    pub pattern_file: Option<String>,
     pub path: String,
+    pub paths: Vec<String>,
+    pub null_data: bool,
+    pub text: bool,
+    pub multiline: bool,
+    pub dotall: bool,
+    pub recursive: bool,
+    pub follow_symlinks: bool,
+    pub stats: bool,
+    pub max_depth: Option<usize>,
+    pub line_buffered: bool,
+    pub progress: bool,
+    pub color: ColorMode,
+    pub count: bool,
+    pub line_number: bool,
+    pub field_separator: Option<String>,
+    pub warn_empty: bool,
+    pub invert: bool,
+    pub fixed_strings: bool,
+    pub ignore_case: bool,
+    pub no_filename: bool,
+    pub with_filename: bool,
+    pub unique: bool,
+    pub unique_global: bool,
+    pub only_matching: bool,
+    pub files_with_matches: bool,
+    pub encoding: Encoding,
+    pub ignore_bad_patterns: bool,
+    pub context: Option<usize>,
+    pub tsv: bool,
+    pub sort: SortMode,
+    pub column: bool,
+    pub match_filename: bool,
+    pub max_line_length: Option<usize>,
+    pub record_separator: Option<String>,
+    pub max_count_per_file: Option<usize>,
+    pub files_from: Option<String>,
+    pub help: bool,
+    pub version: bool,
 }
 
 impl Arguments {
-    /// Given an iterator of strings, returns the corresponding Arguments
+    /// Given an iterator of strings, returns the corresponding Arguments.
+    /// The flags must be placed before the regex and the path, see Arguments::parse for any order.
+    ///
+    /// List of supported flags:
+    ///
+    /// * '-z', '--null' - Lines are separated by the NUL character instead of a newline
+    /// * '-a', '--text' - Binary files are searched as if they were text
+    /// * '--multiline' - The whole file is searched at once, and the matching regions are returned
+    /// * '-s', '--dotall' - The point also matches a newline in multiline mode
+    /// * '-r', '--recursive' - If the path is a directory, all the files inside it are searched
+    /// * '-S', '--follow-symlinks' - The recursive search also descends into symbolic links to directories,
+    ///   each directory is searched only once to avoid loops
+    /// * '--stats' - A summary of the searched files is printed after the search
+    /// * '--max-depth N' - The recursive search does not descend more than N directories
+    /// * '--line-buffered' - Each line is printed and flushed as soon as it is found,
+    ///   before the rest of the file or the standard input is read
+    /// * '--progress' - The amount of searched lines of each file is reported on stderr, if it is a terminal
+    /// * '--color[=WHEN]' - The matches are highlighted and the file name and line number prefixes are colored,
+    ///   WHEN is 'always', 'never' or 'auto' (the default), in auto mode only if the output is a terminal
+    /// * '-c', '--count' - Only the amount of matching lines of each file is printed
+    /// * '-n', '--line-number' - Each line is prefixed with its line number
+    /// * '--field-separator SEP' - The prefixes of each line are separated by SEP instead of ':'
+    /// * '--warn-empty' - An empty file is reported as an error instead of having no matches
+    /// * '-v', '--invert-match' - The lines that do not match the regex are selected
+    /// * '-F', '--fixed-strings' - The regex is searched as literal text, without metacharacters
+    /// * '-i', '--ignore-case' - Uppercase and lowercase letters are matched as the same letter,
+    ///   also with '-F'
+    /// * '-h', '--no-filename' - The lines are not prefixed with the file name when many files are searched
+    /// * '-H', '--with-filename' - The lines are prefixed with the file name even when one file is searched
+    /// * '--unique' - Identical matching lines are printed only once
+    /// * '--unique-global' - Identical matching lines are printed only once across all the searched files,
+    ///   the first time they are found
+    /// * '-o', '--only-matching' - Only the matched parts of each line are printed, one per line
+    /// * '-l', '--files-with-matches' - Only the names of the files with a match are printed
+    /// * '--encoding ENC' - The files are decoded as 'utf8' (the default) or 'latin1'
+    /// * '-e PATTERN', '--regexp PATTERN' - The PATTERN is searched, it can be repeated to search many patterns,
+    ///   and a line matches if any of them matches it. With this flag every argument that is not a flag is a path
+    /// * '-f FILE', '--pattern-file FILE' - Each non empty line of FILE is searched as a pattern, as with '-e'
+    /// * '--ignore-bad-patterns' - The invalid alternatives of the regex are reported and skipped,
+    ///   and the search continues with the valid ones
+    /// * '-C N', '--context N' - N lines before and after each matching line are also printed,
+    ///   overlapping blocks are merged and separated blocks are divided by a '--' line
+    /// * '--tsv' - Each matching line is printed as its number, its amount of matches and its text,
+    ///   separated by tabs
+    /// * '--sort[=MODE]' - The printed lines are sorted, MODE is 'alphabetic' (the default)
+    ///   or 'numeric', which sorts by the number at the start of each line
+    /// * '--column' - Each line is prefixed with its line number and the column of its first match,
+    ///   counted in characters from 1
+    /// * '--match-filename' - The regex is matched against the path of each file instead of its contents,
+    ///   and the matching paths are printed
+    /// * '--max-line-length N' - The printed lines longer than N characters are truncated with "...",
+    ///   the whole line is still matched
+    /// * '--record-separator REGEX' - The text is split into records where REGEX matches, at the start of a line,
+    ///   and each record, that can span many lines, is matched instead of each line
+    /// * '--max-count-per-file N' - At most N matching lines of each file are selected,
+    ///   the context lines after the last one are still printed
+    /// * '--files-from FILE' - Each non blank line of FILE is a path to be searched, after the paths of the arguments,
+    ///   so the paths of the arguments are not needed
+    /// * '--help' - Only the usage of the program is printed, the regex and path are not needed
+    /// * '--version' - Only the version of the program is printed, the regex and path are not needed
+    ///
+    /// Contradictory flags can not be combined: '-o' with '-v', '-c' or '-l', '-l' with '-c',
+    /// '--multiline' with '-v', '--tsv' with '-o', '-c', '-v' or '--multiline',
+    /// '--column' with '-v' or '--multiline', and '--record-separator' with '--multiline'.
     ///
     /// # Arguments
     ///
@@ -40,16 +261,34 @@ impl Arguments {
     /// assert_eq!(arguments.path, "path".to_string());
     /// ```
     ///
-    pub fn new(mut args: impl Iterator<Item = String>) -> Result<Arguments, ProgramError> {
-        args.next();
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Arguments, ProgramError> {
+        let mut args = args.skip(1).peekable();
+        let mut arguments = Arguments::default();
 
-        let regex = match args.next() {
-            Some(arg) => arg,
-            None => return Err(ProgramError::ArgumentMissing),
-        };
+        while let Some(flag) = args.next_if(|arg| is_flag(arg)) {
+            arguments.set_flag(&flag, &mut args)?;
+        }
+        if args.peek().is_some_and(|arg| is_unknown_flag(arg)) {
+            return Err(ProgramError::UnknownFlag);
+        }
+
+        if arguments.help || arguments.version {
+            return Ok(arguments);
+        }
+
+        if arguments.patterns.is_empty() && arguments.pattern_file.is_none() {
+            arguments.regex = match args.next() {
+                Some(arg) => arg,
+                None => return Err(ProgramError::ArgumentMissing),
+            };
+        }
 
-        let path = match args.next() {
-            Some(arg) => arg,
+        arguments.paths = args.next().into_iter().collect();
+        if let Some(list) = &arguments.files_from {
+            arguments.paths.extend(read_paths(list.clone())?);
+        }
+        arguments.path = match arguments.paths.first() {
+            Some(path) => path.clone(),
             None => return Err(ProgramError::PathMissing),
         };
 
@@ -57,226 +296,4083 @@ impl Arguments {
             return Err(ProgramError::InvalidAmountOfArguments);
         }
 
-        Ok(Arguments { regex, path })
+        arguments.validate()?;
+        Ok(arguments)
+    }
+
+    /// Given an iterator of strings, returns the corresponding Arguments.
+    /// The flags can be placed before, after or between the regex and the paths,
+    /// and every argument after "--" is taken as the regex or a path.
+    /// Any other argument that starts with '-' and is not a supported flag is an unknown flag.
+    /// The first argument that is not a flag is the regex, and the rest are the paths to be searched.
+    /// The supported flags are the same as in Arguments::new.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - An iterator of strings
+    ///
+    /// # Returns
+    ///
+    /// * Arguments - The corresponding Arguments if they are valid
+    /// * ProgramError - The corresponding error if the Arguments are invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::Arguments;
+    ///
+    /// let binding = { vec!["rgrep", "regex", "-r", "path1", "--", "-path2"] };
+    ///
+    /// let args = binding.iter().map(|s| s.to_string());
+    ///
+    /// let arguments = Arguments::parse(args).unwrap();
+    /// assert_eq!(arguments.regex, "regex".to_string());
+    /// assert_eq!(arguments.paths, vec!["path1", "-path2"]);
+    /// assert!(arguments.recursive);
+    /// ```
+    ///
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Arguments, ProgramError> {
+        let mut args = args.skip(1);
+        let mut arguments = Arguments::default();
+        let mut positionals = Vec::new();
+
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                positionals.extend(args.by_ref());
+            } else if is_flag(&arg) {
+                arguments.set_flag(&arg, &mut args)?;
+            } else if is_unknown_flag(&arg) {
+                return Err(ProgramError::UnknownFlag);
+            } else {
+                positionals.push(arg);
+            }
+        }
+
+        if arguments.help || arguments.version {
+            return Ok(arguments);
+        }
+
+        let mut positionals = positionals.into_iter();
+        if arguments.patterns.is_empty() && arguments.pattern_file.is_none() {
+            arguments.regex = match positionals.next() {
+                Some(arg) => arg,
+                None => return Err(ProgramError::ArgumentMissing),
+            };
+        }
+
+        arguments.paths = positionals.collect();
+        if let Some(list) = &arguments.files_from {
+            arguments.paths.extend(read_paths(list.clone())?);
+        }
+        arguments.path = match arguments.paths.first() {
+            Some(path) => path.clone(),
+            None => return Err(ProgramError::PathMissing),
+        };
+
+        arguments.validate()?;
+        Ok(arguments)
+    }
+
+    /// Returns an error if the Arguments have flags that contradict each other
+    ///
+    fn validate(&self) -> Result<(), ProgramError> {
+        let incompatible = [
+            (self.only_matching, self.invert),
+            (self.only_matching, self.count),
+            (self.only_matching, self.files_with_matches),
+            (self.files_with_matches, self.count),
+            (self.multiline, self.invert),
+            (self.tsv, self.only_matching),
+            (self.tsv, self.count),
+            (self.tsv, self.invert),
+            (self.tsv, self.multiline),
+            (self.column, self.invert),
+            (self.column, self.multiline),
+            (self.record_separator.is_some(), self.multiline),
+        ];
+
+        if incompatible.iter().any(|(first, second)| *first && *second) {
+            return Err(ProgramError::IncompatibleFlags);
+        }
+        Ok(())
+    }
+
+    /// Given a flag and the remaining arguments, sets the corresponding option.
+    /// Flags that take a value read it from the remaining arguments.
+    ///
+    fn set_flag(
+        &mut self,
+        flag: &str,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<(), ProgramError> {
+        match flag {
+            "-z" | "--null" => self.null_data = true,
+            "-a" | "--text" => self.text = true,
+            "--multiline" => self.multiline = true,
+            "-s" | "--dotall" => self.dotall = true,
+            "-r" | "--recursive" => self.recursive = true,
+            "-S" | "--follow-symlinks" => self.follow_symlinks = true,
+            "--stats" => self.stats = true,
+            "--line-buffered" => self.line_buffered = true,
+            "--progress" => self.progress = true,
+            "-c" | "--count" => self.count = true,
+            "-n" | "--line-number" => self.line_number = true,
+            "--warn-empty" => self.warn_empty = true,
+            "-v" | "--invert-match" => self.invert = true,
+            "-F" | "--fixed-strings" => self.fixed_strings = true,
+            "-i" | "--ignore-case" => self.ignore_case = true,
+            "-h" | "--no-filename" => self.no_filename = true,
+            "-H" | "--with-filename" => self.with_filename = true,
+            "--unique" => self.unique = true,
+            "--unique-global" => self.unique_global = true,
+            "-o" | "--only-matching" => self.only_matching = true,
+            "-l" | "--files-with-matches" => self.files_with_matches = true,
+            "--ignore-bad-patterns" => self.ignore_bad_patterns = true,
+            "--tsv" => self.tsv = true,
+            "--column" => self.column = true,
+            "--match-filename" => self.match_filename = true,
+            "--help" => self.help = true,
+            "--version" => self.version = true,
+            "--record-separator" => match args.next() {
+                Some(separator) => self.record_separator = Some(separator),
+                None => return Err(ProgramError::InvalidFlagValue),
+            },
+            "--field-separator" => match args.next() {
+                Some(separator) => self.field_separator = Some(separator),
+                None => return Err(ProgramError::InvalidFlagValue),
+            },
+            "--max-depth" => self.max_depth = Some(parse_number(args.next())?),
+            "--max-line-length" => self.max_line_length = Some(parse_number(args.next())?),
+            "--max-count-per-file" => self.max_count_per_file = Some(parse_number(args.next())?),
+            "--files-from" => match args.next() {
+                Some(path) => self.files_from = Some(path),
+                None => return Err(ProgramError::InvalidFlagValue),
+            },
+            "-C" | "--context" => self.context = Some(parse_number(args.next())?),
+            "--encoding" => self.encoding = parse_encoding(args.next())?,
+            "-e" | "--regexp" => match args.next() {
+                Some(pattern) => self.patterns.push(pattern),
+                None => return Err(ProgramError::InvalidFlagValue),
+            },
+            "-f" | "--pattern-file" => match args.next() {
+                Some(path) => {
+                    self.patterns.extend(read_patterns(path.clone())?);
+                    self.pattern_file = Some(path);
+                }
+                None => return Err(ProgramError::InvalidFlagValue),
+            },
+            "--color" => self.color = ColorMode::Auto,
+            "--sort" => self.sort = SortMode::Alphabetic,
+            _ => {
+                if let Some(when) = flag.strip_prefix("--color=") {
+                    self.color = parse_color(when)?;
+                } else if let Some(mode) = flag.strip_prefix("--sort=") {
+                    self.sort = parse_sort(mode)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns if the matches are highlighted in the output.
+    /// In auto mode the matches are only highlighted if the standard output is a terminal.
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the matches are highlighted
+    ///
+    pub fn use_color(&self) -> bool {
+        match self.color {
+            ColorMode::Never => false,
+            ColorMode::Always => true,
+            ColorMode::Auto => io::stdout().is_terminal(),
+        }
+    }
+
+    /// Returns the string that separates the prefixes of each line from the line
+    ///
+    /// # Returns
+    ///
+    /// * &str - The field separator if it is set, ":" otherwise
+    ///
+    pub fn field_separator(&self) -> &str {
+        self.field_separator.as_deref().unwrap_or(":")
+    }
+
+    /// Given if more than one file is searched, returns if the lines are prefixed with the file name.
+    /// Only the lines of a search over many files are prefixed, unless the no filename flag is set.
+    /// With the with filename flag, the lines of a single file are also prefixed
+    ///
+    /// # Arguments
+    ///
+    /// * `many_files` - If more than one file is searched
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the file name is added before each line
+    ///
+    pub fn show_filename(&self, many_files: bool) -> bool {
+        (many_files || self.with_filename) && !self.no_filename
+    }
+
+    /// Returns the options of the regex to be searched, the same for every way of searching the files.
+    /// The text of each file is decoded before it is searched, so it is matched in unicode mode,
+    /// and with the ignore case flag the letters are matched ignoring their case.
+    /// In multiline mode the anchors match at each line, and the point matches a newline with the dotall flag
+    ///
+    /// # Returns
+    ///
+    /// * RegexOptions - The options of the regex
+    ///
+    pub fn regex_options(&self) -> RegexOptions {
+        RegexOptions {
+            case_insensitive: self.ignore_case,
+            unicode: true,
+            multiline: self.multiline,
+            dotall: self.multiline && self.dotall,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the regex to be searched.
+    /// The patterns of the regexp flag are joined as the alternatives of a single regex,
+    /// so a line matches if any of them matches it.
+    /// With the fixed strings flag, every metacharacter of each pattern is escaped, including '|'.
+    /// With the ignore bad patterns flag, only the valid alternatives are kept
+    ///
+    /// # Returns
+    ///
+    /// * String - The regex to be searched
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::Arguments;
+    ///
+    /// let args = vec!["rgrep", "-F", "a.c|d", "path"].into_iter().map(|s| s.to_string());
+    /// let arguments = Arguments::parse(args).unwrap();
+    ///
+    /// assert_eq!(arguments.pattern(), "a\\.c\\|d");
+    ///
+    /// let args = vec!["rgrep", "-e", "abc", "-e", "d.f", "path"].into_iter().map(|s| s.to_string());
+    /// let arguments = Arguments::parse(args).unwrap();
+    ///
+    /// assert_eq!(arguments.pattern(), "abc|d.f");
+    /// ```
+    ///
+    pub fn pattern(&self) -> String {
+        self.checked_pattern().0
+    }
+
+    /// Returns the errors of the invalid alternatives of the regex, skipped with the ignore bad patterns flag
+    ///
+    /// # Returns
+    ///
+    /// * Vec<String> - The error of each skipped alternative, empty if the flag is not set
+    ///
+    pub fn pattern_errors(&self) -> Vec<String> {
+        self.checked_pattern().1
+    }
+
+    /// Returns the regex to be searched with the errors of its skipped alternatives,
+    /// as in pattern and pattern_errors, checking the alternatives only once
+    ///
+    fn checked_pattern(&self) -> (String, Vec<String>) {
+        if self.ignore_bad_patterns {
+            valid_alternatives(&self.joined_patterns())
+        } else {
+            (self.joined_patterns(), Vec::new())
+        }
+    }
+
+    /// Returns the compiled regex to be searched with the errors of its skipped alternatives.
+    /// The regex is compiled once with the options of regex_options, to be used for every file
+    ///
+    fn compiled_pattern(&self) -> Result<(CompiledPattern, Vec<String>), String> {
+        let (pattern, errors) = self.checked_pattern();
+        let compiled = CompiledPattern::with_options(&pattern, &self.regex_options())?;
+        Ok((compiled, errors))
+    }
+
+    /// Returns the patterns to be searched joined as the alternatives of a single regex
+    ///
+    fn joined_patterns(&self) -> String {
+        let patterns = if self.patterns.is_empty() {
+            std::slice::from_ref(&self.regex)
+        } else {
+            &self.patterns
+        };
+
+        patterns
+            .iter()
+            .map(|pattern| {
+                if self.fixed_strings {
+                    escape(pattern)
+                } else {
+                    pattern.clone()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("|")
+    }
+
+    /// Returns if each selected line is printed as soon as it is read, with the line buffered flag.
+    /// Sorting, counting, the context lines, the names of the files and the searches over the whole text
+    /// need every line of a file first, so they are printed when the search ends
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the lines are printed while the files are read
+    ///
+    pub fn streams_lines(&self) -> bool {
+        self.line_buffered
+            && self.sort == SortMode::Unsorted
+            && !self.multiline
+            && !self.tsv
+            && !self.count
+            && !self.files_with_matches
+            && !self.match_filename
+            && self.context.is_none()
+            && self.record_separator.is_none()
+            && !self.show_progress()
+    }
+
+    /// Returns if the progress of the search is reported, only when stderr is a terminal
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the progress is reported
+    ///
+    pub fn show_progress(&self) -> bool {
+        self.progress && io::stderr().is_terminal()
+    }
+
+    /// Returns the character that separates the lines of the file
+    ///
+    /// # Returns
+    ///
+    /// * char - The NUL character if the null flag is set, a newline otherwise
+    ///
+    pub fn separator(&self) -> char {
+        if self.null_data {
+            '\0'
+        } else {
+            '\n'
+        }
     }
 }
 
-/// Given a regex and a text, returns the lines that match the regex.
-/// It also separates the regex by the character '|', and evaluates each regex separately.
-///
-/// # Arguments
-///
-/// * `regex_str` - A string that represents a regex
-/// * `text` - A string that represents a text
+/// Returns the usage of the program, with the list of supported flags
 ///
 /// # Returns
 ///
-/// * Vec<String> - The lines that match the regex
-/// * String - The error if the regex is invalid
+/// * String - The usage of the program
 ///
 /// # Examples
 ///
 /// ```
-/// use rgrep::run_rgrep;
+/// use rgrep::usage;
 ///
-/// let text = "abcd\nabecd\nab10cd".to_string();
+/// assert!(usage().starts_with("Usage: rgrep [FLAGS] REGEX PATH..."));
+/// ```
 ///
-/// let regex_str = "ab.cd".to_string();
-/// let result = run_rgrep(regex_str, text.clone()).unwrap();
-/// assert_eq!(result, vec!["abecd"]);
+pub fn usage() -> String {
+    [
+        "Usage: rgrep [FLAGS] REGEX PATH...",
+        "Searches the lines that match the REGEX in each PATH.",
+        "",
+        "Flags:",
+        "  -z, --null             Lines are separated by the NUL character",
+        "  -a, --text             Binary files are searched as text",
+        "  --multiline            The whole file is searched at once",
+        "  -s, --dotall           The point matches a newline in multiline mode",
+        "  -r, --recursive        Directories are searched recursively",
+        "  -S, --follow-symlinks  Symbolic links to directories are also searched",
+        "  --max-depth N          Recursive search descends at most N directories",
+        "  --stats                A summary of the search is printed",
+        "  --line-buffered        Each line is printed as soon as it is found",
+        "  --progress             The progress of the search is reported on stderr",
+        "  --color[=WHEN]         Matches are highlighted: always, never or auto",
+        "  -c, --count            Only the amount of matching lines is printed",
+        "  -n, --line-number      Each line is prefixed with its line number",
+        "  --field-separator SEP  Prefixes are separated by SEP instead of ':'",
+        "  --warn-empty           Empty files are reported as errors",
+        "  -v, --invert-match     The lines that do not match are selected",
+        "  -F, --fixed-strings    The REGEX is searched as literal text",
+        "  -i, --ignore-case      Letters are matched ignoring their case",
+        "  -h, --no-filename      Lines are not prefixed with the file name",
+        "  -H, --with-filename    Lines are prefixed with the file name, even for one file",
+        "  --unique               Each distinct matching line is printed once",
+        "  --unique-global        Each distinct line is printed once across all files",
+        "  -o, --only-matching    Only the matched parts of each line are printed",
+        "  -l, --files-with-matches  Only the names of the files with matches are printed",
+        "  --encoding ENC         Files are decoded as utf8 or latin1",
+        "  --ignore-bad-patterns  Invalid alternatives are reported and skipped",
+        "  -C, --context N        N lines around each matching line are printed",
+        "  --tsv                  Line numbers, match counts and lines are printed as TSV",
+        "  --sort[=MODE]          Lines are sorted: alphabetic or numeric",
+        "  --column               Lines are prefixed with the column of the first match",
+        "  --match-filename       The REGEX is matched against the paths of the files",
+        "  --max-line-length N    Printed lines are truncated to N characters",
+        "  --record-separator REGEX  Records starting where REGEX matches are searched",
+        "  --max-count-per-file N At most N matching lines of each file are selected",
+        "  --files-from FILE      Each line of FILE is a path to be searched",
+        "  -e, --regexp PATTERN   The PATTERN is searched, it can be repeated",
+        "  -f, --pattern-file FILE  Each line of FILE is searched as a pattern",
+        "  --help                 The usage is printed",
+        "  --version              The version is printed",
+        "  --                     The following arguments are not flags",
+    ]
+    .join("\n")
+}
+
+/// Returns the version of the program
 ///
-/// let regex_str = "ab.*cd".to_string();
+/// # Returns
 ///
-/// let result = run_rgrep(regex_str, text).unwrap();
-/// assert_eq!(result, vec!["abcd", "abecd", "ab10cd"]);
+/// * String - The name and version of the program
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::version;
+///
+/// assert!(version().starts_with("rgrep "));
 /// ```
 ///
-pub fn run_rgrep(regex_str: String, text: String) -> Result<Vec<String>, String> {
-    let iter = text.split('\n');
-    let mut correct_lines: Vec<String> = Vec::new();
+pub fn version() -> String {
+    format!("rgrep {}", env!("CARGO_PKG_VERSION"))
+}
 
-    let regex_vec = regex_str.split('|');
-    let mut bad_regex = "".to_string();
-    let mut regex_temp;
-    'regex: for mut regex in regex_vec {
-        if regex.ends_with('\\') {
-            bad_regex = regex.to_string();
-            continue 'regex;
-        }
+/// Given an argument, returns if it is one of the supported flags
+///
+fn is_flag(arg: &str) -> bool {
+    FLAGS.contains(&arg) || arg.starts_with("--color=") || arg.starts_with("--sort=")
+}
 
-        if !bad_regex.is_empty() {
-            regex_temp = regex.to_string();
-            regex_temp.insert(0, '|');
-            regex_temp.insert_str(0, &bad_regex);
-            regex = &regex_temp;
-            bad_regex = "".to_string();
-        }
+/// Given an argument, returns if it looks like a flag but is not one of the supported flags.
+/// A lone "-" is the standard input, not a flag.
+///
+fn is_unknown_flag(arg: &str) -> bool {
+    arg.starts_with('-') && arg != STDIN_PATH && !is_flag(arg)
+}
 
-        let regex = Regex::new(regex)?;
-        let mut counter = 0;
+/// Given the value of the encoding flag, returns the corresponding Encoding
+///
+fn parse_encoding(value: Option<String>) -> Result<Encoding, ProgramError> {
+    match value.as_deref() {
+        Some("utf8" | "utf-8") => Ok(Encoding::Utf8),
+        Some("latin1" | "latin-1" | "iso-8859-1") => Ok(Encoding::Latin1),
+        _ => Err(ProgramError::InvalidFlagValue),
+    }
+}
 
-        for line in iter.clone() {
-            if correct_lines.contains(&line.to_string()) {
-                counter += 1;
-            } else {
-                let evaluation = regex.clone().evaluate(line)?;
-                if evaluation.result {
-                    correct_lines.insert(counter, evaluation.line);
-                    counter += 1;
-                }
-            }
-        }
+/// Given the value of the color flag, returns the corresponding ColorMode
+///
+fn parse_color(value: &str) -> Result<ColorMode, ProgramError> {
+    match value {
+        "never" => Ok(ColorMode::Never),
+        "always" => Ok(ColorMode::Always),
+        "auto" => Ok(ColorMode::Auto),
+        _ => Err(ProgramError::InvalidFlagValue),
+    }
+}
+
+/// Given the value of the sort flag, returns the corresponding SortMode
+///
+fn parse_sort(value: &str) -> Result<SortMode, ProgramError> {
+    match value {
+        "alphabetic" => Ok(SortMode::Alphabetic),
+        "numeric" => Ok(SortMode::Numeric),
+        _ => Err(ProgramError::InvalidFlagValue),
     }
+}
+
+/// Given the path of a pattern file, returns the patterns it contains, one per line.
+/// Empty lines are skipped, as they would match every line.
+///
+fn read_patterns(path: String) -> Result<Vec<String>, ProgramError> {
+    let text = read_file(path)?;
+    Ok(text
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect())
+}
 
-    Ok(correct_lines)
+/// Given the path of a file with a list of paths, returns the paths it contains, one per line.
+/// Blank lines are skipped.
+///
+fn read_paths(path: String) -> Result<Vec<String>, ProgramError> {
+    let text = read_file(path)?;
+    Ok(text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.to_string())
+        .collect())
 }
 
-/// Given a vector of strings, prints each string
+/// Given the value of a flag, returns the number it represents
+///
+fn parse_number(value: Option<String>) -> Result<usize, ProgramError> {
+    match value.map(|value| value.parse::<usize>()) {
+        Some(Ok(number)) => Ok(number),
+        _ => Err(ProgramError::InvalidFlagValue),
+    }
+}
+
+/// Given a regex and a text, returns the lines that match the regex, in the order of the text.
+/// A line matches if any of the alternatives separated by the character '|' matches it.
+/// Identical matching lines are all returned, as each one is a different line of the text.
 ///
 /// # Arguments
 ///
-/// * `lines` - A vector of strings
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+///
+/// # Returns
+///
+/// * Vec<String> - The lines that match the regex
+/// * String - The error if the regex is invalid
 ///
 /// # Examples
 ///
 /// ```
-/// use rgrep::print_lines;
+/// use rgrep::run_rgrep;
 ///
-/// let lines = vec!["abcd".to_string(), "efgh".to_string()];
-/// print_lines(lines);
+/// let text = "abcd\nabecd\nab10cd";
+///
+/// let result = run_rgrep("ab.cd", text).unwrap();
+/// assert_eq!(result, vec!["abecd"]);
+///
+/// let result = run_rgrep("ab.*cd", text).unwrap();
+/// assert_eq!(result, vec!["abcd", "abecd", "ab10cd"]);
 /// ```
 ///
-pub fn print_lines(lines: Vec<String>) {
-    for line in lines {
-        println!("{}", line);
-    }
+pub fn run_rgrep(regex_str: &str, text: &str) -> Result<Vec<String>, String> {
+    run_rgrep_separated(regex_str, text, '\n')
+}
+
+#[derive(Debug, Default)]
+pub struct SearchResult {
+    pub lines: Vec<String>,
+    pub total_lines_scanned: usize,
+    pub matched_lines: usize,
+    pub elapsed: Duration,
 }
 
-/// Given a path, returns the text of the file
+/// Given a regex and a text, returns the lines that match the regex, as in run_rgrep,
+/// with the amount of lines scanned, the amount of matching lines and the time spent searching.
 ///
 /// # Arguments
 ///
-/// * `path` - A string that represents the path of the file
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
 ///
 /// # Returns
 ///
-/// * String - The text of the file
-/// * ProgramError - The error if the file is invalid
+/// * SearchResult - The matching lines and the metadata of the search
+/// * String - The error if the regex is invalid
 ///
 /// # Examples
 ///
 /// ```
-/// use rgrep::read_file;
+/// use rgrep::run_rgrep_detailed;
 ///
-/// let text = read_file("res/test2.txt".to_string()).unwrap();
+/// let result = run_rgrep_detailed("ab", "abcd\nefgh\nab").unwrap();
 ///
-/// assert_eq!(text, "aaa\nee|oo\neo\nqqqq|\n|pppp\n".to_string());
+/// assert_eq!(result.lines, vec!["abcd", "ab"]);
+/// assert_eq!(result.total_lines_scanned, 3);
+/// assert_eq!(result.matched_lines, 2);
 /// ```
 ///
-pub fn read_file(path: String) -> Result<String, ProgramError> {
-    let text = fs::read_to_string(path);
-    match text {
-        Ok(text) => Ok(text),
-        Err(err) => Err(process_error(Box::new(err))),
-    }
-}
+pub fn run_rgrep_detailed(regex_str: &str, text: &str) -> Result<SearchResult, String> {
+    let start = Instant::now();
+    let regex = compile_regex(regex_str, &RegexOptions::default())?;
+    let mut result = SearchResult::default();
 
-fn process_error(err: Box<dyn Error>) -> ProgramError {
-    match err {
-        err if err.to_string().contains("No such file or directory") => {
-            ProgramError::InvalidFilePath
-        }
-        err if err
-            .to_string()
-            .contains("stream did not contain valid UTF-8") =>
-        {
-            ProgramError::InvalidFileFormat
+    for line in split_lines(text, '\n') {
+        result.total_lines_scanned += 1;
+        let evaluation = regex.evaluate(line)?;
+        if evaluation.result {
+            result.matched_lines += 1;
+            result.lines.push(evaluation.line);
         }
-        _ => ProgramError::ErrorWhileReadingFile,
     }
+
+    result.elapsed = start.elapsed();
+    Ok(result)
 }
 
-/// Given an error, prints the error
+/// Given a regex, a text and a separator, returns the lines that match the regex.
+/// The lines of the text are separated by the given separator instead of a newline.
 ///
 /// # Arguments
 ///
-/// * `err` - A string that represents the error
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+/// * `separator` - The character that separates the lines of the text
+///
+/// # Returns
+///
+/// * Vec<String> - The lines that match the regex
+/// * String - The error if the regex is invalid
 ///
 /// # Examples
 ///
 /// ```
-/// use rgrep::print_error;
+/// use rgrep::run_rgrep_separated;
 ///
-/// print_error("Error while reading file");
+/// let text = "abcd\0ab\ncd\0efgh";
+///
+/// let result = run_rgrep_separated("cd", text, '\0').unwrap();
+/// assert_eq!(result, vec!["abcd", "ab\ncd"]);
 /// ```
 ///
-pub fn print_error(err: &str) {
-    writeln!(&mut std::io::stderr(), "rgrep: {}", err).unwrap_or_else(|_| ());
+pub fn run_rgrep_separated(
+    regex_str: &str,
+    text: &str,
+    separator: char,
+) -> Result<Vec<String>, String> {
+    MatchingLines::new(
+        compile_regex(regex_str, &RegexOptions::default()).map(Cow::Owned),
+        text,
+        separator,
+        false,
+    )
+    .map(|found| found.map(|(_, line)| line))
+    .collect()
 }
 
-#[cfg(test)]
+/// Given a regex and a text, returns an iterator over the lines that match the regex.
+/// Lines are evaluated while the iterator is consumed, so stopping early does not scan the rest of the text.
+/// As in run_rgrep, a line matches if any of the alternatives of the regex matches it.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+///
+/// # Returns
+///
+/// * Iterator - The lines that match the regex, or the error if the regex or a line is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::run_rgrep_iter;
+///
+/// let text = "abcd\nabecd\nab10cd";
+///
+/// let result: Vec<String> = run_rgrep_iter("ab.*cd", text).take(2).map(|line| line.unwrap()).collect();
+/// assert_eq!(result, vec!["abcd", "abecd"]);
+/// ```
+///
+pub fn run_rgrep_iter<'t>(
+    regex_str: &str,
+    text: &'t str,
+) -> impl Iterator<Item = Result<String, String>> + 't {
+    MatchingLines::new(
+        compile_regex(regex_str, &RegexOptions::default()).map(Cow::Owned),
+        text,
+        '\n',
+        false,
+    )
+    .map(|found| found.map(|(_, line)| line))
+}
+
+/// Given a regex, a text and a separator, returns the lines that match the regex with their line number.
+/// Lines are numbered from 1.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+/// * `separator` - The character that separates the lines of the text
+///
+/// # Returns
+///
+/// * Vec<(usize, String)> - The line number and the text of each matching line
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::run_rgrep_numbered;
+///
+/// let text = "abcd\nefgh\nabef";
+///
+/// let result = run_rgrep_numbered("ab", text, '\n').unwrap();
+/// assert_eq!(result, vec![(1, "abcd".to_string()), (3, "abef".to_string())]);
+/// ```
+///
+pub fn run_rgrep_numbered(
+    regex_str: &str,
+    text: &str,
+    separator: char,
+) -> Result<Vec<(usize, String)>, String> {
+    MatchingLines::new(
+        compile_regex(regex_str, &RegexOptions::default()).map(Cow::Owned),
+        text,
+        separator,
+        false,
+    )
+    .collect()
+}
+
+/// Given a regex, a text and a separator, returns the matching lines of the text without repeating them.
+/// A line equal to a previous matching line is skipped, so only the first of the identical lines is kept
+/// with its line number.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+/// * `separator` - The character that separates the lines of the text
+///
+/// # Returns
+///
+/// * Vec<(usize, String)> - The line number and the text of each distinct matching line
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::run_rgrep_unique;
+///
+/// let text = "abcd\nefgh\nabcd\nabef";
+///
+/// let result = run_rgrep_unique("ab", text, '\n').unwrap();
+/// assert_eq!(result, vec![(1, "abcd".to_string()), (4, "abef".to_string())]);
+/// ```
+///
+pub fn run_rgrep_unique(
+    regex_str: &str,
+    text: &str,
+    separator: char,
+) -> Result<Vec<(usize, String)>, String> {
+    CompiledPattern::new(regex_str)?.unique_lines(text, separator)
+}
+
+/// Given a regex, a text, a separator and a writer, returns the lines that match the regex with their line number,
+/// as in run_rgrep_numbered, while the progress of the search is written to the writer.
+/// Every few thousand lines, and once all the lines are searched, a status line that starts with a
+/// carriage return is written, with the percentage of the text and the amount of lines already searched.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+/// * `separator` - The character that separates the lines of the text
+/// * `progress` - The writer where the progress is reported, like stderr
+///
+/// # Returns
+///
+/// * Vec<(usize, String)> - The line number and the text of each matching line
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::run_rgrep_progress;
+///
+/// let text = "abcd\nefgh\nabef";
+/// let mut progress = Vec::new();
+///
+/// let result = run_rgrep_progress("ab", text, '\n', &mut progress).unwrap();
+/// assert_eq!(result, vec![(1, "abcd".to_string()), (3, "abef".to_string())]);
+/// assert_eq!(progress, b"\r100% (3 lines)\n");
+/// ```
+///
+pub fn run_rgrep_progress(
+    regex_str: &str,
+    text: &str,
+    separator: char,
+    progress: &mut impl Write,
+) -> Result<Vec<(usize, String)>, String> {
+    CompiledPattern::new(regex_str)?.matching_lines_with_progress(text, separator, progress)
+}
+
+/// Given a writer, the amount of processed bytes, the total bytes and the searched lines,
+/// writes the status line of the progress followed by the given end.
+/// A failure to write the progress does not stop the search.
+///
+fn write_progress(
+    progress: &mut impl Write,
+    processed: usize,
+    total: usize,
+    lines: usize,
+    end: &str,
+) {
+    let percent = (processed * 100).checked_div(total).unwrap_or(100);
+    write!(progress, "\r{}% ({} lines){}", percent, lines, end).unwrap_or(());
+    progress.flush().unwrap_or(());
+}
+
+/// Given a regex, a text and a separator, returns the lines that do not match the regex with their line number.
+/// A line is selected if none of the alternatives of the regex match it.
+/// A separator at the end of the text does not start a new line.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+/// * `separator` - The character that separates the lines of the text
+///
+/// # Returns
+///
+/// * Vec<(usize, String)> - The line number and the text of each line that does not match
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::run_rgrep_inverted;
+///
+/// let text = "abcd\nefgh\nabef\n";
+///
+/// let result = run_rgrep_inverted("ab", text, '\n').unwrap();
+/// assert_eq!(result, vec![(2, "efgh".to_string())]);
+/// ```
+///
+pub fn run_rgrep_inverted(
+    regex_str: &str,
+    text: &str,
+    separator: char,
+) -> Result<Vec<(usize, String)>, String> {
+    CompiledPattern::new(regex_str)?.inverted_lines(text, separator)
+}
+
+/// Given a text and the character that separates its lines, returns the lines of the text.
+/// The separator that ends the text is not followed by an empty line, so a pattern
+/// like '^' or '$' does not match an extra line after it.
+///
+pub(crate) fn split_lines(text: &str, separator: char) -> std::str::Split<'_, char> {
+    text.strip_suffix(separator)
+        .unwrap_or(text)
+        .split(separator)
+}
+
+/// Iterator over the lines of a text that match the regex, with their line number.
+/// If unique, a line equal to a previous match is skipped.
+///
+struct MatchingLines<'t> {
+    regex: Result<Cow<'t, Regex>, String>,
+    lines: std::iter::Enumerate<std::str::Split<'t, char>>,
+    unique: bool,
+    found: HashSet<String>,
+    finished: bool,
+}
+
+impl<'t> MatchingLines<'t> {
+    fn new(
+        regex: Result<Cow<'t, Regex>, String>,
+        text: &'t str,
+        separator: char,
+        unique: bool,
+    ) -> Self {
+        MatchingLines {
+            regex,
+            lines: split_lines(text, separator).enumerate(),
+            unique,
+            found: HashSet::new(),
+            finished: false,
+        }
+    }
+}
+
+impl Iterator for MatchingLines<'_> {
+    type Item = Result<(usize, String), String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let regex = match &self.regex {
+            Ok(regex) => regex,
+            Err(err) => {
+                self.finished = true;
+                return Some(Err(err.clone()));
+            }
+        };
+
+        // The regex is compiled once and borrowed for every line, instead of being cloned per line,
+        // and each matching line is yielded as found, so no intermediate vector of lines is built.
+        for (index, line) in self.lines.by_ref() {
+            if self.unique && self.found.contains(line) {
+                continue;
+            }
+
+            match regex.evaluate(line) {
+                Ok(evaluation) if evaluation.result => {
+                    if self.unique {
+                        self.found.insert(evaluation.line.clone());
+                    }
+                    return Some(Ok((index + 1, evaluation.line)));
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err.to_string()));
+                }
+            }
+        }
+
+        self.finished = true;
+        None
+    }
+}
+
+/// Given a regex and a text, returns the regions of the text that match the regex.
+/// The whole text is searched at once, so a match can span multiple lines.
+/// The anchors match at the start and end of each line, and the point only matches a newline in dotall mode.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+/// * `dotall` - If the point matches a newline
+///
+/// # Returns
+///
+/// * Vec<String> - The regions of the text that match the regex
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::run_rgrep_multiline;
+///
+/// let text = "abc\ndef\nghi";
+///
+/// let result = run_rgrep_multiline("c[[:space:]]d", text, false).unwrap();
+/// assert_eq!(result, vec!["c\nd"]);
+/// ```
+///
+pub fn run_rgrep_multiline(
+    regex_str: &str,
+    text: &str,
+    dotall: bool,
+) -> Result<Vec<String>, String> {
+    let options = RegexOptions {
+        unicode: true,
+        ..Default::default()
+    };
+    run_rgrep_multiline_with(regex_str, text, dotall, &options)
+}
+
+/// Given a regex, a text, if the point matches a newline and the options of the regex,
+/// returns the regions of the text that match the regex, as in run_rgrep_multiline
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+/// * `dotall` - If the point matches a newline
+/// * `options` - The options used to compile the regex, always in multiline mode
+///
+/// # Returns
+///
+/// * Vec<String> - The regions of the text that match the regex
+/// * String - The error if the regex is invalid
+///
+pub fn run_rgrep_multiline_with(
+    regex_str: &str,
+    text: &str,
+    dotall: bool,
+    options: &RegexOptions,
+) -> Result<Vec<String>, String> {
+    let options = RegexOptions {
+        multiline: true,
+        dotall,
+        ..options.clone()
+    };
+    Ok(CompiledPattern::with_options(regex_str, &options)?.regions(text))
+}
+
+/// Given a regex that matches the start of each record and a text, returns the records of the text.
+/// A record starts where the regex matches and ends before the next record, so it can span many lines,
+/// and the text before the first match is also a record. The newline that ends each record is removed.
+/// The anchors match at the start and end of each line.
+///
+/// # Arguments
+///
+/// * `separator_str` - A string that represents the regex of the start of each record
+/// * `text` - A string that represents a text
+///
+/// # Returns
+///
+/// * Vec<&str> - The records of the text
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::split_records;
+///
+/// let text = "[1] start\nmore\n[2] end\n";
+///
+/// let result = split_records("^\\[", text).unwrap();
+/// assert_eq!(result, vec!["[1] start\nmore", "[2] end"]);
+/// ```
+///
+pub fn split_records<'t>(separator_str: &str, text: &'t str) -> Result<Vec<&'t str>, String> {
+    let options = RegexOptions {
+        multiline: true,
+        unicode: true,
+        ..Default::default()
+    };
+    let separator = compile_regex(separator_str, &options)?;
+
+    let mut starts: Vec<usize> = separator
+        .find_iter(text)
+        .map(|found| found.start)
+        .filter(|start| *start > 0)
+        .collect();
+    starts.dedup();
+
+    let mut records = Vec::new();
+    let mut previous = 0;
+    for start in starts.into_iter().chain(std::iter::once(text.len())) {
+        let record = &text[previous..start];
+        if start < text.len() || !record.is_empty() {
+            records.push(record.strip_suffix('\n').unwrap_or(record));
+        }
+        previous = start;
+    }
+
+    Ok(records)
+}
+
+/// A regex compiled once, to be applied to many texts without compiling it again
+///
+#[derive(Debug, Clone)]
+pub struct CompiledPattern {
+    regex: Regex,
+}
+
+impl CompiledPattern {
+    /// Given a string, returns the compiled pattern if the string is a valid regex
+    ///
+    /// # Arguments
+    ///
+    /// * `regex_str` - A string that represents a regex
+    ///
+    /// # Returns
+    ///
+    /// * CompiledPattern - The compiled pattern
+    /// * String - The error if the regex is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::CompiledPattern;
+    ///
+    /// let pattern = CompiledPattern::new("ab|cd").unwrap();
+    ///
+    /// assert_eq!(pattern.matching_lines("ab\nef", '\n').unwrap(), vec![(1, "ab".to_string())]);
+    /// assert_eq!(pattern.matching_lines("ef\ncd", '\n').unwrap(), vec![(2, "cd".to_string())]);
+    /// ```
+    ///
+    pub fn new(regex_str: &str) -> Result<Self, String> {
+        CompiledPattern::with_options(regex_str, &RegexOptions::default())
+    }
+
+    /// Given a string and the options of the regex, returns the compiled pattern if the string is a valid regex
+    ///
+    /// # Arguments
+    ///
+    /// * `regex_str` - A string that represents a regex
+    /// * `options` - The options of the regex
+    ///
+    /// # Returns
+    ///
+    /// * CompiledPattern - The compiled pattern
+    /// * String - The error if the regex is invalid
+    ///
+    pub fn with_options(regex_str: &str, options: &RegexOptions) -> Result<Self, String> {
+        Ok(CompiledPattern {
+            regex: compile_regex(regex_str, options)?,
+        })
+    }
+
+    /// Given a string, returns if the pattern matches any part of it
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked, like a line or a path
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the pattern matches the string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::CompiledPattern;
+    ///
+    /// let pattern = CompiledPattern::new("\\.rs$").unwrap();
+    ///
+    /// assert!(pattern.is_match("src/main.rs"));
+    /// assert!(!pattern.is_match("src/main.rs.txt"));
+    /// ```
+    ///
+    pub fn is_match(&self, value: &str) -> bool {
+        self.regex.find(value).is_some()
+    }
+
+    /// Given a line, returns the column where the first match of the pattern starts.
+    /// The column is counted in characters from 1, so it does not depend on the size of each character
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string that represents a line
+    ///
+    /// # Returns
+    ///
+    /// * Option<usize> - The column of the first match, None if the line does not match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::CompiledPattern;
+    ///
+    /// let pattern = CompiledPattern::new("cd").unwrap();
+    ///
+    /// assert_eq!(pattern.first_column("abcdef"), Some(3));
+    /// assert_eq!(pattern.first_column("abef"), None);
+    /// ```
+    ///
+    pub fn first_column(&self, line: &str) -> Option<usize> {
+        let found = self.regex.find(line)?;
+        Some(line[..found.start].chars().count() + 1)
+    }
+
+    /// Given a text and a separator, returns the lines that match the pattern with their line number,
+    /// as in run_rgrep_numbered
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string that represents a text
+    /// * `separator` - The character that separates the lines of the text
+    ///
+    /// # Returns
+    ///
+    /// * Vec<(usize, String)> - The line number and the text of each matching line
+    /// * String - The error if a line is invalid
+    ///
+    pub fn matching_lines(
+        &self,
+        text: &str,
+        separator: char,
+    ) -> Result<Vec<(usize, String)>, String> {
+        MatchingLines::new(Ok(Cow::Borrowed(&self.regex)), text, separator, false).collect()
+    }
+
+    /// Given a text and a separator, returns the matching lines without repeating them, as in run_rgrep_unique
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string that represents a text
+    /// * `separator` - The character that separates the lines of the text
+    ///
+    /// # Returns
+    ///
+    /// * Vec<(usize, String)> - The line number and the text of each distinct matching line
+    /// * String - The error if a line is invalid
+    ///
+    pub fn unique_lines(
+        &self,
+        text: &str,
+        separator: char,
+    ) -> Result<Vec<(usize, String)>, String> {
+        MatchingLines::new(Ok(Cow::Borrowed(&self.regex)), text, separator, true).collect()
+    }
+
+    /// Given a text and a separator, returns the amount of lines that do not match the pattern.
+    /// The lines are only counted, so no vector of lines is built.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string that represents a text
+    /// * `separator` - The character that separates the lines of the text
+    ///
+    /// # Returns
+    ///
+    /// * usize - The amount of lines that do not match
+    /// * String - The error if a line is invalid
+    ///
+    pub fn count_inverted(&self, text: &str, separator: char) -> Result<usize, String> {
+        let mut count = 0;
+        for line in split_lines(text, separator) {
+            if !self.regex.evaluate(line)?.result {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Given a text and a separator, returns the lines that do not match the pattern, as in run_rgrep_inverted
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string that represents a text
+    /// * `separator` - The character that separates the lines of the text
+    ///
+    /// # Returns
+    ///
+    /// * Vec<(usize, String)> - The line number and the text of each line that does not match
+    /// * String - The error if a line is invalid
+    ///
+    pub fn inverted_lines(
+        &self,
+        text: &str,
+        separator: char,
+    ) -> Result<Vec<(usize, String)>, String> {
+        let mut lines = Vec::new();
+        for (index, line) in split_lines(text, separator).enumerate() {
+            if !self.regex.evaluate(line)?.result {
+                lines.push((index + 1, line.to_string()));
+            }
+        }
+
+        Ok(lines)
+    }
+
+    /// Given a text, returns the regions of the text that match the pattern, as in run_rgrep_multiline.
+    /// The whole text is searched at once, so the anchors and the point follow the options of the pattern
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string that represents a text
+    ///
+    /// # Returns
+    ///
+    /// * Vec<String> - The regions of the text that match the pattern
+    ///
+    pub fn regions(&self, text: &str) -> Vec<String> {
+        self.regex
+            .find_iter(text)
+            .map(|found| found.as_str(text).to_string())
+            .collect()
+    }
+
+    /// Given a text, returns the line number and the amount of matches of every line that matches,
+    /// as in matches_per_line
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string that represents a text
+    ///
+    /// # Returns
+    ///
+    /// * Vec<(usize, usize)> - The line number and the amount of matches of each matching line
+    ///
+    pub fn matches_per_line(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut counts = Vec::new();
+        for (number, line) in split_lines(text, '\n').enumerate() {
+            let mut found = self.regex.find_iter(line).peekable();
+            if found.peek().is_none() {
+                continue;
+            }
+            counts.push((number + 1, found.filter(|m| m.start < m.end).count()));
+        }
+        counts
+    }
+
+    /// Given a text, returns a tab separated row for every line that matches, as in tsv_lines
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string that represents a text
+    ///
+    /// # Returns
+    ///
+    /// * Vec<String> - The row of each matching line
+    ///
+    pub fn tsv_lines(&self, text: &str) -> Vec<String> {
+        let lines: Vec<&str> = split_lines(text, '\n').collect();
+
+        self.matches_per_line(text)
+            .into_iter()
+            .map(|(number, count)| {
+                format!("{}\t{}\t{}", number, count, escape_tsv(lines[number - 1]))
+            })
+            .collect()
+    }
+
+    /// Given a slice of lines, returns the non-empty matches of the pattern in each line
+    /// with the position of the line in the slice, as in only_matching
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The lines to be searched
+    ///
+    /// # Returns
+    ///
+    /// * Vec<(usize, String)> - The position of the line and the matched text of each match
+    ///
+    pub fn only_matching(&self, lines: &[String]) -> Vec<(usize, String)> {
+        let mut found = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            for part in self.regex.find_iter(line) {
+                if part.start < part.end {
+                    found.push((index, part.as_str(line).to_string()));
+                }
+            }
+        }
+        found
+    }
+
+    /// Given a slice of lines, returns the lines with the matches of the pattern highlighted, as in highlight_lines
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The lines to be highlighted
+    ///
+    /// # Returns
+    ///
+    /// * Vec<String> - The highlighted lines
+    ///
+    pub fn highlight_lines(&self, lines: &[String]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| {
+                let mut result = String::new();
+                let mut last = 0;
+                for found in self.regex.find_iter(line) {
+                    if found.start == found.end {
+                        continue;
+                    }
+                    result.push_str(&line[last..found.start]);
+                    result.push_str(COLOR_MATCH);
+                    result.push_str(found.as_str(line));
+                    result.push_str(COLOR_RESET);
+                    last = found.end;
+                }
+                result.push_str(&line[last..]);
+                result
+            })
+            .collect()
+    }
+
+    /// Given a text, a separator and a writer, returns the lines that match the pattern
+    /// while the progress is written to the writer, as in run_rgrep_progress
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string that represents a text
+    /// * `separator` - The character that separates the lines of the text
+    /// * `progress` - The writer where the progress is reported
+    ///
+    /// # Returns
+    ///
+    /// * Vec<(usize, String)> - The line number and the text of each matching line
+    /// * String - The error if a line is invalid
+    ///
+    pub fn matching_lines_with_progress(
+        &self,
+        text: &str,
+        separator: char,
+        progress: &mut impl Write,
+    ) -> Result<Vec<(usize, String)>, String> {
+        let mut lines = Vec::new();
+        let mut processed = 0;
+        let mut searched = 0;
+        for (index, line) in split_lines(text, separator).enumerate() {
+            if self.regex.evaluate(line)?.result {
+                lines.push((index + 1, line.to_string()));
+            }
+
+            searched = index + 1;
+            processed = (processed + line.len() + separator.len_utf8()).min(text.len());
+            if searched % PROGRESS_INTERVAL == 0 {
+                write_progress(progress, processed, text.len(), searched, "");
+            }
+        }
+        write_progress(progress, processed, text.len(), searched, "\n");
+
+        Ok(lines)
+    }
+}
+
+/// Given a regex, returns a regex with only its valid alternatives, and the errors of the invalid ones.
+/// The alternatives are separated by the '|' characters that are not escaped,
+/// nor inside a group or a bracket expression. Empty alternatives are skipped.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+///
+/// # Returns
+///
+/// * String - The regex made of the valid alternatives
+/// * Vec<String> - The error of each invalid alternative, prefixed with the alternative
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::valid_alternatives;
+///
+/// let (regex, errors) = valid_alternatives("d(e|f)|abc|[");
+///
+/// assert_eq!(regex, "d(e|f)|abc");
+/// assert_eq!(errors.len(), 1);
+/// assert!(errors[0].starts_with("[: "));
+/// ```
+///
+pub fn valid_alternatives(regex_str: &str) -> (String, Vec<String>) {
+    let mut valid = Vec::new();
+    let mut errors = Vec::new();
+
+    // The empty alternatives are ignored by the regex, so they are neither kept nor reported
+    for alternative in split_alternatives(regex_str)
+        .into_iter()
+        .filter(|alternative| !alternative.is_empty())
+    {
+        match Regex::new(alternative) {
+            Ok(_) => valid.push(alternative),
+            Err(err) => errors.push(format!("{}: {}", alternative, err)),
+        }
+    }
+
+    (valid.join("|"), errors)
+}
+
+/// Given a regex, returns its top level alternatives
+///
+fn split_alternatives(regex_str: &str) -> Vec<&str> {
+    let mut alternatives = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    let mut in_bracket = false;
+    let mut chars = regex_str.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' if in_bracket && chars.peek().is_some_and(|(_, next)| *next == ':') => {
+                // A class like [:digit:] does not close the bracket expression
+                while let Some((_, c)) = chars.next() {
+                    if c == ':' && chars.next_if(|(_, next)| *next == ']').is_some() {
+                        break;
+                    }
+                }
+            }
+            '[' if !in_bracket => {
+                // A ']' right after '[' or '[^' is a member, not the end of the bracket expression
+                in_bracket = true;
+                chars.next_if(|(_, next)| *next == '^');
+                chars.next_if(|(_, next)| *next == ']');
+            }
+            ']' if in_bracket => in_bracket = false,
+            '(' if !in_bracket => depth += 1,
+            ')' if !in_bracket && depth > 0 => depth -= 1,
+            '|' if !in_bracket && depth == 0 => {
+                alternatives.push(&regex_str[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(&regex_str[start..]);
+    alternatives
+}
+
+/// Given a regex and the options of the regex, returns the compiled regex
+///
+fn compile_regex(regex_str: &str, options: &RegexOptions) -> Result<Regex, String> {
+    Ok(Regex::with_options(regex_str, options)?)
+}
+
+/// Given a regex and a text, returns the total amount of matches of the regex in the text.
+/// Matches are counted per line without overlapping, taking the first alternative that matches on each position.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+///
+/// # Returns
+///
+/// * usize - The total amount of matches
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::count_total_matches;
+///
+/// let text = "abcab\nab";
+///
+/// let result = count_total_matches("ab", text).unwrap();
+/// assert_eq!(result, 3);
+/// ```
+///
+pub fn count_total_matches(regex_str: &str, text: &str) -> Result<usize, String> {
+    let regex = compile_regex(regex_str, &RegexOptions::default())?;
+
+    let mut total = 0;
+    for line in split_lines(text, '\n') {
+        if !line.is_ascii() {
+            return Err(RegexError::NoAsciiCharacter.message().to_string());
+        }
+        total += regex.find_iter(line).count();
+    }
+
+    Ok(total)
+}
+
+/// Given a regex and a text, returns the line number and the amount of matches of every line that matches.
+/// Lines are numbered from 1 and kept in the order of the text, lines without matches are skipped.
+/// A line matches even if the regex only matches an empty string in it, but only the non empty matches
+/// are counted. The newline that ends the text is not followed by an extra line.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+///
+/// # Returns
+///
+/// * Vec<(usize, usize)> - The line number and the amount of matches of each matching line
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::matches_per_line;
+///
+/// let text = "aaa\nb\nba";
+///
+/// let result = matches_per_line("a", text).unwrap();
+/// assert_eq!(result, vec![(1, 3), (3, 1)]);
+/// ```
+///
+pub fn matches_per_line(regex_str: &str, text: &str) -> Result<Vec<(usize, usize)>, String> {
+    let options = RegexOptions {
+        unicode: true,
+        ..Default::default()
+    };
+    matches_per_line_with(regex_str, text, &options)
+}
+
+/// Given a regex, a text and the options of the regex, returns the line number and the amount of matches
+/// of every line that matches, as in matches_per_line
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+/// * `options` - The options used to compile the regex
+///
+/// # Returns
+///
+/// * Vec<(usize, usize)> - The line number and the amount of matches of each matching line
+/// * String - The error if the regex is invalid
+///
+pub fn matches_per_line_with(
+    regex_str: &str,
+    text: &str,
+    options: &RegexOptions,
+) -> Result<Vec<(usize, usize)>, String> {
+    Ok(CompiledPattern::with_options(regex_str, options)?.matches_per_line(text))
+}
+
+/// Given a regex and a text, returns a tab separated row for every line that matches,
+/// with the line number, the amount of matches and the text of the line.
+/// The tabs, newlines and backslashes of the text are escaped, so each row has exactly three fields.
+/// The matches are counted as in matches_per_line, so an empty match selects the line but is not counted.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+///
+/// # Returns
+///
+/// * Vec<String> - The row of each matching line
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::tsv_lines;
+///
+/// let text = "a1b22\nnone\n3\t4";
+///
+/// let result = tsv_lines("[[:digit:]]", text).unwrap();
+/// assert_eq!(result, vec!["1\t3\ta1b22", "3\t2\t3\\t4"]);
+/// ```
+///
+pub fn tsv_lines(regex_str: &str, text: &str) -> Result<Vec<String>, String> {
+    let options = RegexOptions {
+        unicode: true,
+        ..Default::default()
+    };
+    tsv_lines_with(regex_str, text, &options)
+}
+
+/// Given a regex, a text and the options of the regex, returns a tab separated row
+/// for every line that matches, as in tsv_lines
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `text` - A string that represents a text
+/// * `options` - The options used to compile the regex
+///
+/// # Returns
+///
+/// * Vec<String> - The row of each matching line
+/// * String - The error if the regex is invalid
+///
+pub fn tsv_lines_with(
+    regex_str: &str,
+    text: &str,
+    options: &RegexOptions,
+) -> Result<Vec<String>, String> {
+    Ok(CompiledPattern::with_options(regex_str, options)?.tsv_lines(text))
+}
+
+/// Given a field of a TSV row, returns it with its backslashes, tabs, newlines and carriage returns escaped
+///
+fn escape_tsv(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Given a regex and a slice of lines, returns the non-empty matches of the regex in each line,
+/// with the position of the line in the slice.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `lines` - The lines to be searched
+///
+/// # Returns
+///
+/// * Vec<(usize, String)> - The position of the line and the matched text of each match
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::only_matching;
+///
+/// let lines = vec!["abcab".to_string(), "cd".to_string(), "xab".to_string()];
+///
+/// let result = only_matching("ab", &lines).unwrap();
+/// assert_eq!(result[0], (0, "ab".to_string()));
+/// assert_eq!(result[2], (2, "ab".to_string()));
+/// ```
+///
+pub fn only_matching(regex_str: &str, lines: &[String]) -> Result<Vec<(usize, String)>, String> {
+    let options = RegexOptions {
+        unicode: true,
+        ..Default::default()
+    };
+    only_matching_with(regex_str, lines, &options)
+}
+
+/// Given a regex, a slice of lines and the options of the regex, returns the non-empty matches
+/// of the regex in each line, as in only_matching
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `lines` - The lines to be searched
+/// * `options` - The options used to compile the regex
+///
+/// # Returns
+///
+/// * Vec<(usize, String)> - The position of the line and the matched text of each match
+/// * String - The error if the regex is invalid
+///
+pub fn only_matching_with(
+    regex_str: &str,
+    lines: &[String],
+    options: &RegexOptions,
+) -> Result<Vec<(usize, String)>, String> {
+    Ok(CompiledPattern::with_options(regex_str, options)?.only_matching(lines))
+}
+
+/// Given a regex and a slice of lines, returns the lines with the matches of the regex highlighted.
+/// Each match is wrapped between the escape codes of the color of the matches and the reset code.
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `lines` - The lines to be highlighted
+///
+/// # Returns
+///
+/// * Vec<String> - The highlighted lines
+/// * String - The error if the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::highlight_lines;
+///
+/// let lines = vec!["abcb".to_string()];
+///
+/// let result = highlight_lines("b", &lines).unwrap();
+/// assert_eq!(result, vec!["a\x1b[01;31mb\x1b[0mc\x1b[01;31mb\x1b[0m"]);
+/// ```
+///
+pub fn highlight_lines(regex_str: &str, lines: &[String]) -> Result<Vec<String>, String> {
+    let options = RegexOptions {
+        unicode: true,
+        ..Default::default()
+    };
+    highlight_lines_with(regex_str, lines, &options)
+}
+
+/// Given a regex, a slice of lines and the options of the regex, returns the lines
+/// with the matches of the regex highlighted, as in highlight_lines
+///
+/// # Arguments
+///
+/// * `regex_str` - A string that represents a regex
+/// * `lines` - The lines to be highlighted
+/// * `options` - The options used to compile the regex
+///
+/// # Returns
+///
+/// * Vec<String> - The highlighted lines
+/// * String - The error if the regex is invalid
+///
+pub fn highlight_lines_with(
+    regex_str: &str,
+    lines: &[String],
+    options: &RegexOptions,
+) -> Result<Vec<String>, String> {
+    Ok(CompiledPattern::with_options(regex_str, options)?.highlight_lines(lines))
+}
+
+/// Given a vector of strings, prints each string
+///
+/// # Arguments
+///
+/// * `lines` - A vector of strings
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::print_lines;
+///
+/// let lines = vec!["abcd".to_string(), "efgh".to_string()];
+/// print_lines(lines);
+/// ```
+///
+pub fn print_lines(lines: Vec<String>) {
+    print_lines_separated(lines, '\n');
+}
+
+/// Given a vector of strings and a separator, prints each string followed by the separator
+///
+/// # Arguments
+///
+/// * `lines` - A vector of strings
+/// * `separator` - The character printed after each string
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::print_lines_separated;
+///
+/// let lines = vec!["abcd".to_string(), "efgh".to_string()];
+/// print_lines_separated(lines, '\0');
+/// ```
+///
+pub fn print_lines_separated(lines: Vec<String>, separator: char) {
+    write_lines(&lines, separator, &mut std::io::stdout()).unwrap_or(());
+}
+
+/// Given a slice of strings, a separator and an output, writes each string followed by the separator
+///
+/// # Arguments
+///
+/// * `lines` - A slice of strings
+/// * `separator` - The character written after each string
+/// * `out` - The output where the strings are written
+///
+/// # Returns
+///
+/// * io::Error - The error if the output could not be written
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::write_lines;
+///
+/// let lines = vec!["abcd".to_string(), "efgh".to_string()];
+/// let mut out = Vec::new();
+/// write_lines(&lines, '\n', &mut out).unwrap();
+///
+/// assert_eq!(out, b"abcd\nefgh\n");
+/// ```
+///
+pub fn write_lines<W: Write>(lines: &[String], separator: char, out: &mut W) -> io::Result<()> {
+    for line in lines {
+        write!(out, "{}{}", line, separator)?;
+    }
+    Ok(())
+}
+
+/// Given the lines to be printed and a SortMode, sorts the lines.
+/// In numeric mode the lines are sorted by the number at their start, a line without one counts as 0,
+/// and lines with the same number are sorted alphabetically.
+///
+/// # Arguments
+///
+/// * `lines` - The lines to be sorted
+/// * `mode` - How the lines are sorted, they are kept in order if unsorted
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::{sort_lines, SortMode};
+///
+/// let mut lines = vec!["10 b".to_string(), "9 a".to_string(), "x".to_string()];
+///
+/// sort_lines(&mut lines, SortMode::Alphabetic);
+/// assert_eq!(lines, vec!["10 b", "9 a", "x"]);
+///
+/// sort_lines(&mut lines, SortMode::Numeric);
+/// assert_eq!(lines, vec!["x", "9 a", "10 b"]);
+/// ```
+///
+pub fn sort_lines(lines: &mut [String], mode: SortMode) {
+    match mode {
+        SortMode::Unsorted => {}
+        SortMode::Alphabetic => lines.sort(),
+        SortMode::Numeric => lines.sort_by(|a, b| {
+            leading_number(a)
+                .total_cmp(&leading_number(b))
+                .then_with(|| a.cmp(b))
+        }),
+    }
+}
+
+/// Given a line, returns the number at its start, with an optional sign and decimals, or 0 if there is none
+///
+fn leading_number(line: &str) -> f64 {
+    let line = line.trim_start();
+    let mut end = 0;
+    for (i, c) in line.char_indices() {
+        let is_sign = i == 0 && c == '-';
+        if !(c.is_ascii_digit() || c == '.' || is_sign) {
+            break;
+        }
+        end = i + 1;
+    }
+    line[..end].parse().unwrap_or(0.0)
+}
+
+/// Given a vector of strings and a separator, prints each string followed by the separator,
+/// flushing the output after each string so it is shown as soon as it is printed
+///
+/// # Arguments
+///
+/// * `lines` - A vector of strings
+/// * `separator` - The character printed after each string
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::print_lines_flushed;
+///
+/// let lines = vec!["abcd".to_string(), "efgh".to_string()];
+/// print_lines_flushed(lines, '\n');
+/// ```
+///
+pub fn print_lines_flushed(lines: Vec<String>, separator: char) {
+    write_lines_flushed(&lines, separator, &mut std::io::stdout()).unwrap_or(());
+}
+
+/// Given a slice of strings, a separator and an output, writes each string followed by the separator
+/// and flushes the output after each string
+///
+/// # Arguments
+///
+/// * `lines` - A slice of strings
+/// * `separator` - The character written after each string
+/// * `out` - The output where the strings are written
+///
+/// # Returns
+///
+/// * io::Error - The error if the output could not be written or flushed
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::write_lines_flushed;
+///
+/// let lines = vec!["abcd".to_string(), "efgh".to_string()];
+/// let mut out = Vec::new();
+/// write_lines_flushed(&lines, '\0', &mut out).unwrap();
+///
+/// assert_eq!(out, b"abcd\0efgh\0");
+/// ```
+///
+pub fn write_lines_flushed<W: Write>(
+    lines: &[String],
+    separator: char,
+    out: &mut W,
+) -> io::Result<()> {
+    for line in lines {
+        write!(out, "{}{}", line, separator)?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Given a path, returns the text of the file.
+/// If the path is `-`, the text is read from the standard input.
+///
+/// # Arguments
+///
+/// * `path` - A string that represents the path of the file
+///
+/// # Returns
+///
+/// * String - The text of the file
+/// * ProgramError - The error if the file is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::read_file;
+///
+/// let text = read_file("res/test2.txt".to_string()).unwrap();
+///
+/// assert_eq!(text, "aaa\nee|oo\neo\nqqqq|\n|pppp\n".to_string());
+/// ```
+///
+pub fn read_file(path: String) -> Result<String, ProgramError> {
+    let bytes = read_file_bytes(path)?;
+    bytes_to_text(bytes)
+}
+
+/// Given a reader, returns all the text it contains
+///
+/// # Arguments
+///
+/// * `reader` - The source of the text, like a file or the standard input
+///
+/// # Returns
+///
+/// * String - The text read
+/// * ProgramError - The error if the text can not be read or is not valid UTF-8
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::read_text;
+///
+/// let text = read_text("aaa\neo\n".as_bytes()).unwrap();
+///
+/// assert_eq!(text, "aaa\neo\n".to_string());
+/// ```
+///
+pub fn read_text(reader: impl Read) -> Result<String, ProgramError> {
+    let bytes = read_bytes(reader)?;
+    bytes_to_text(bytes)
+}
+
+/// Given a reader, returns all the bytes it contains
+///
+/// # Arguments
+///
+/// * `reader` - The source of the bytes, like a file or the standard input
+///
+/// # Returns
+///
+/// * Vec<u8> - The bytes read
+/// * ProgramError - The error if the bytes can not be read
+///
+pub fn read_bytes(mut reader: impl Read) -> Result<Vec<u8>, ProgramError> {
+    let mut bytes = Vec::new();
+    match reader.read_to_end(&mut bytes) {
+        Ok(_) => Ok(bytes),
+        Err(err) => Err(process_error(err)),
+    }
+}
+
+/// Given a path, returns the bytes of the file.
+/// If the path is `-`, the bytes are read from the standard input.
+/// Both absolute and relative paths are accepted, with the separators of the platform,
+/// and a matching pair of quotes around the path is removed.
+///
+/// # Arguments
+///
+/// * `path` - A string that represents the path of the file
+///
+/// # Returns
+///
+/// * Vec<u8> - The bytes of the file
+/// * ProgramError - The error if the file can not be read
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::read_file_bytes;
+///
+/// let bytes = read_file_bytes("res/test2.txt".to_string()).unwrap();
+///
+/// assert_eq!(bytes, b"aaa\nee|oo\neo\nqqqq|\n|pppp\n");
+/// ```
+///
+pub fn read_file_bytes(path: String) -> Result<Vec<u8>, ProgramError> {
+    if path == STDIN_PATH {
+        return read_bytes(io::stdin().lock());
+    }
+    let bytes = fs::read(normalize_path(&path));
+    match bytes {
+        Ok(bytes) => Ok(bytes),
+        Err(err) => Err(process_error(err)),
+    }
+}
+
+/// Given the bytes of a file, returns the text of the file
+///
+fn bytes_to_text(bytes: Vec<u8>) -> Result<String, ProgramError> {
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(text),
+        Err(_) => Err(ProgramError::InvalidFileFormat),
+    }
+}
+
+/// Given the bytes of a Latin-1 file, returns the text of the file.
+/// Every byte is decoded as the char with the same code, so the decoding never fails
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes of the file
+///
+/// # Returns
+///
+/// * String - The text of the file
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::latin1_to_text;
+///
+/// assert_eq!(latin1_to_text(b"caf\xE9"), "café");
+/// ```
+///
+pub fn latin1_to_text(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| char::from(*byte)).collect()
+}
+
+/// Given the bytes of a file, returns if the file is binary.
+/// A file is considered binary if there is a NUL byte at the start of the file.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes of the file
+///
+/// # Returns
+///
+/// * bool - If the file is binary
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::is_binary;
+///
+/// assert!(is_binary(b"abc\0def"));
+/// assert!(!is_binary(b"abc\ndef"));
+/// ```
+///
+pub fn is_binary(bytes: &[u8]) -> bool {
+    let size = bytes.len().min(BINARY_CHECK_SIZE);
+    bytes[..size].contains(&0)
+}
+
+/// Given the bytes of a binary file, returns a text where the non-ascii bytes are replaced by NUL
+///
+fn binary_to_text(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| if b.is_ascii() { *b as char } else { '\0' })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineKind {
+    Match,
+    Context,
+    BlockSeparator,
+}
+
+#[derive(Debug, Default)]
+pub struct FileMatches {
+    pub path: String,
+    pub lines: Vec<String>,
+    pub numbers: Vec<usize>,
+    pub kinds: Vec<LineKind>,
+    pub columns: Vec<usize>,
+    pub count: Option<usize>,
+    pub binary: bool,
+}
+
+impl FileMatches {
+    /// Returns the lines to be printed for the file.
+    /// A binary file only prints a notice if it matches the regex.
+    /// The path of the file and the line number prefix each line when requested,
+    /// separated from the line by the field separator, or by '-' for a context line.
+    ///
+    /// # Arguments
+    ///
+    /// * `with_filename` - If each line is prefixed with the path of the file
+    /// * `field_separator` - The string between each prefix and the line
+    ///
+    /// # Returns
+    ///
+    /// * Vec<String> - The lines to be printed
+    ///
+    pub fn output(&self, with_filename: bool, field_separator: &str) -> Vec<String> {
+        self.output_with_colors(with_filename, field_separator, None)
+    }
+
+    /// Returns the lines to be printed for the file, as in output,
+    /// with each prefix wrapped in the escape codes of its color when a color scheme is given.
+    /// The separators between the fields are not colored.
+    ///
+    /// # Arguments
+    ///
+    /// * `with_filename` - If each line is prefixed with the path of the file
+    /// * `field_separator` - The string between each prefix and the line
+    /// * `colors` - The colors of the prefixes, None to print them without colors
+    ///
+    /// # Returns
+    ///
+    /// * Vec<String> - The lines to be printed
+    ///
+    pub fn output_with_colors(
+        &self,
+        with_filename: bool,
+        field_separator: &str,
+        colors: Option<&ColorScheme>,
+    ) -> Vec<String> {
+        let paint = |field: &str, color: fn(&ColorScheme) -> &'static str| match colors {
+            Some(colors) => format!("{}{}{}", color(colors), field, COLOR_RESET),
+            None => field.to_string(),
+        };
+
+        if self.binary {
+            if self.lines.is_empty() {
+                return Vec::new();
+            }
+            return vec![format!("Binary file {} matches", self.path)];
+        }
+
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let field_separator = match self.kinds.get(i) {
+                    Some(LineKind::BlockSeparator) => return line.clone(),
+                    Some(LineKind::Context) => "-",
+                    _ => field_separator,
+                };
+                let mut prefix = String::new();
+                if with_filename {
+                    prefix.push_str(&paint(&self.path, |colors| colors.filename));
+                    prefix.push_str(field_separator);
+                }
+                if let Some(number) = self.numbers.get(i) {
+                    prefix.push_str(&paint(&number.to_string(), |colors| colors.line_number));
+                    prefix.push_str(field_separator);
+                }
+                if let Some(column) = self.columns.get(i) {
+                    prefix.push_str(&paint(&column.to_string(), |colors| colors.line_number));
+                    prefix.push_str(field_separator);
+                }
+                prefix + line
+            })
+            .collect()
+    }
+
+    /// Returns the amount of matching lines of the file, without the context lines.
+    /// If the lines were only counted, the counted amount is returned.
+    ///
+    /// # Returns
+    ///
+    /// * usize - The amount of matching lines
+    ///
+    pub fn matching_count(&self) -> usize {
+        if let Some(count) = self.count {
+            count
+        } else if self.kinds.is_empty() {
+            self.lines.len()
+        } else {
+            self.kinds
+                .iter()
+                .filter(|kind| **kind == LineKind::Match)
+                .count()
+        }
+    }
+
+    /// Returns the line with the amount of matching lines of the file
+    ///
+    /// # Arguments
+    ///
+    /// * `with_filename` - If the amount is prefixed with the path of the file
+    /// * `field_separator` - The string between the path and the amount
+    ///
+    /// # Returns
+    ///
+    /// * String - The line to be printed
+    ///
+    pub fn count_output(&self, with_filename: bool, field_separator: &str) -> String {
+        if with_filename {
+            format!("{}{}{}", self.path, field_separator, self.matching_count())
+        } else {
+            self.matching_count().to_string()
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SearchStats {
+    pub files_searched: usize,
+    pub files_matched: usize,
+    pub matching_lines: usize,
+}
+
+impl SearchStats {
+    /// Returns the summary of the search
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::SearchStats;
+    ///
+    /// let stats = SearchStats {
+    ///     files_searched: 3,
+    ///     files_matched: 2,
+    ///     matching_lines: 5,
+    /// };
+    ///
+    /// assert_eq!(stats.summary(), "searched 3 files, 2 matched, 5 matching lines");
+    /// ```
+    ///
+    pub fn summary(&self) -> String {
+        format!(
+            "searched {} files, {} matched, {} matching lines",
+            self.files_searched, self.files_matched, self.matching_lines
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SearchOutput {
+    pub lines: Vec<String>,
+    pub errors: Vec<String>,
+    pub stats: SearchStats,
+}
+
+impl SearchOutput {
+    /// Returns the exit code of the program for the search, as grep does.
+    /// In count mode the code also depends on the amount of matching lines, not on the printed counts
+    ///
+    /// # Returns
+    ///
+    /// * i32 - 2 if a file could not be searched, even if other files had selected lines,
+    ///   0 if a line was selected and 1 if no line was selected
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::{run_search, Arguments};
+    ///
+    /// let binding = { vec!["rgrep", "-c", "regex", "res/dir/b.txt"] };
+    /// let args = binding.iter().map(|s| s.to_string());
+    /// let arguments = Arguments::parse(args).unwrap();
+    ///
+    /// let output = run_search(&arguments).unwrap();
+    /// assert_eq!(output.lines, vec!["0"]);
+    /// assert_eq!(output.exit_code(), 1);
+    /// ```
+    ///
+    pub fn exit_code(&self) -> i32 {
+        if !self.errors.is_empty() {
+            EXIT_ERROR
+        } else if self.stats.matching_lines > 0 {
+            EXIT_MATCH
+        } else {
+            EXIT_NO_MATCH
+        }
+    }
+}
+
+/// Given the Arguments, the compiled pattern and the path of a file, reads the file and returns its matching lines
+///
+fn search_path(
+    arguments: &Arguments,
+    compiled: &CompiledPattern,
+    path: &str,
+) -> Result<FileMatches, String> {
+    let bytes = match read_file_bytes(path.to_string()) {
+        Ok(bytes) => bytes,
+        Err(err) => return Err(err.message().to_string()),
+    };
+    if arguments.warn_empty && bytes.is_empty() {
+        return Err(ProgramError::EmptyFile.message().to_string());
+    }
+    search_bytes(arguments, compiled, path, bytes)
+}
+
+/// Given the Arguments, the compiled pattern, the path of a file and its bytes, returns the matching lines of the file.
+/// A binary file is searched with its non-ascii bytes replaced, and any other file is decoded with its encoding
+///
+fn search_bytes(
+    arguments: &Arguments,
+    compiled: &CompiledPattern,
+    path: &str,
+    bytes: Vec<u8>,
+) -> Result<FileMatches, String> {
+    let separator = arguments.separator();
+
+    if !arguments.text && !arguments.null_data && is_binary(&bytes) {
+        let text = binary_to_text(&bytes);
+        let mut lines = if arguments.invert {
+            compiled
+                .inverted_lines(&text, separator)?
+                .into_iter()
+                .map(|(_, line)| line)
+                .collect()
+        } else {
+            compiled
+                .matching_lines(&text, separator)?
+                .into_iter()
+                .map(|(_, line)| line)
+                .collect()
+        };
+        if let Some(max) = arguments.max_count_per_file {
+            limit_matches(&mut lines, &mut Vec::new(), &mut Vec::new(), max);
+        }
+        return Ok(FileMatches {
+            path: path.to_string(),
+            lines,
+            numbers: Vec::new(),
+            kinds: Vec::new(),
+            columns: Vec::new(),
+            count: None,
+            binary: true,
+        });
+    }
+
+    let text = match arguments.encoding {
+        Encoding::Utf8 => match bytes_to_text(bytes) {
+            Ok(text) => text,
+            Err(err) => return Err(err.message().to_string()),
+        },
+        Encoding::Latin1 => latin1_to_text(&bytes),
+    };
+    search_text(arguments, compiled, path, &text)
+}
+
+/// Given the Arguments, the compiled pattern, the path of a file and its decoded text,
+/// returns the lines of the text to be printed
+///
+fn search_text(
+    arguments: &Arguments,
+    compiled: &CompiledPattern,
+    path: &str,
+    text: &str,
+) -> Result<FileMatches, String> {
+    let separator = arguments.separator();
+
+    if arguments.count
+        && arguments.invert
+        && arguments.record_separator.is_none()
+        && !arguments.unique
+        && !arguments.unique_global
+        && !arguments.show_progress()
+    {
+        // Only the amount of lines that do not match is printed, so they are counted
+        // without building a vector with the lines
+        let count = compiled.count_inverted(text, separator)?;
+        return Ok(FileMatches {
+            path: path.to_string(),
+            count: Some(
+                arguments
+                    .max_count_per_file
+                    .map_or(count, |max| count.min(max)),
+            ),
+            ..Default::default()
+        });
+    }
+
+    let context = arguments.context.filter(|_| {
+        !arguments.multiline && !arguments.count && !arguments.only_matching && !arguments.tsv
+    });
+    let with_numbers = arguments.line_number || arguments.column;
+    let mut numbers = Vec::new();
+    let mut kinds = Vec::new();
+    let mut lines = if arguments.multiline {
+        compiled.regions(text)
+    } else if let Some(record_separator) = &arguments.record_separator {
+        split_records(record_separator, text)?
+            .into_iter()
+            .filter(|record| compiled.is_match(record) != arguments.invert)
+            .map(|record| record.to_string())
+            .collect()
+    } else if arguments.tsv {
+        compiled.tsv_lines(text)
+    } else if arguments.invert
+        || with_numbers
+        || arguments.unique
+        || arguments.show_progress()
+        || context.is_some()
+    {
+        let numbered = if arguments.invert {
+            compiled.inverted_lines(text, separator)?
+        } else if arguments.unique {
+            compiled.unique_lines(text, separator)?
+        } else if arguments.show_progress() {
+            compiled.matching_lines_with_progress(text, separator, &mut io::stderr())?
+        } else {
+            compiled.matching_lines(text, separator)?
+        };
+        let (found_numbers, found_lines): (Vec<usize>, Vec<String>) = numbered.into_iter().unzip();
+        if let Some(context) = context {
+            let with_context = context_lines(text, separator, &found_numbers, context);
+            kinds = with_context.iter().map(|(_, kind, _)| *kind).collect();
+            if with_numbers {
+                numbers = with_context.iter().map(|(number, _, _)| *number).collect();
+            }
+            with_context.into_iter().map(|(_, _, line)| line).collect()
+        } else {
+            if with_numbers {
+                numbers = found_numbers;
+            }
+            found_lines
+        }
+    } else {
+        compiled
+            .matching_lines(text, separator)?
+            .into_iter()
+            .map(|(_, line)| line)
+            .collect()
+    };
+
+    if let Some(max) = arguments.max_count_per_file {
+        limit_matches(&mut lines, &mut numbers, &mut kinds, max);
+    }
+
+    if arguments.only_matching && !arguments.multiline {
+        let found = compiled.only_matching(&lines);
+        if !numbers.is_empty() {
+            numbers = found.iter().map(|(index, _)| numbers[*index]).collect();
+        }
+        lines = found.into_iter().map(|(_, part)| part).collect();
+    }
+
+    let mut columns = Vec::new();
+    if arguments.column && kinds.is_empty() && !arguments.only_matching && !arguments.tsv {
+        columns = lines
+            .iter()
+            .map(|line| compiled.first_column(line).unwrap_or(1))
+            .collect();
+    }
+
+    if arguments.use_color() && !arguments.invert && !arguments.tsv {
+        lines = compiled.highlight_lines(&lines);
+    }
+
+    Ok(FileMatches {
+        path: path.to_string(),
+        lines,
+        numbers,
+        kinds,
+        columns,
+        count: None,
+        binary: false,
+    })
+}
+
+/// Given the lines of a file with their numbers and kinds, and the maximum amount of matching lines,
+/// removes the lines after the last allowed match.
+/// The context lines that follow the last allowed match are kept, up to the next block or match.
+///
+fn limit_matches(
+    lines: &mut Vec<String>,
+    numbers: &mut Vec<usize>,
+    kinds: &mut Vec<LineKind>,
+    max: usize,
+) {
+    let len = if kinds.is_empty() || max == 0 {
+        max.min(lines.len())
+    } else {
+        let mut found = 0;
+        kinds
+            .iter()
+            .position(|kind| {
+                if *kind == LineKind::Context {
+                    return false;
+                }
+                if found == max {
+                    return true;
+                }
+                if *kind == LineKind::Match {
+                    found += 1;
+                }
+                false
+            })
+            .unwrap_or(kinds.len())
+    };
+
+    lines.truncate(len);
+    numbers.truncate(len);
+    kinds.truncate(len);
+}
+
+/// Given the numbers of the matching lines, the amount of lines of the text and the amount of context lines,
+/// returns the blocks of lines to be printed, as ranges of line numbers.
+/// The window of each match spans the context lines before and after it,
+/// and windows that overlap or are next to each other are merged into a single block.
+///
+/// # Arguments
+///
+/// * `matches` - The numbers of the matching lines, in ascending order and starting from 1
+/// * `total_lines` - The amount of lines of the text
+/// * `context` - The amount of lines printed before and after each match
+///
+/// # Returns
+///
+/// * Vec<(usize, usize)> - The first and last line number of each block
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::context_blocks;
+///
+/// assert_eq!(context_blocks(&[2, 4, 9], 10, 1), vec![(1, 5), (8, 10)]);
+/// assert_eq!(context_blocks(&[3], 10, 0), vec![(3, 3)]);
+/// ```
+///
+pub fn context_blocks(
+    matches: &[usize],
+    total_lines: usize,
+    context: usize,
+) -> Vec<(usize, usize)> {
+    let mut blocks: Vec<(usize, usize)> = Vec::new();
+
+    for number in matches {
+        let start = number.saturating_sub(context).max(1);
+        let end = (number + context).min(total_lines);
+
+        match blocks.last_mut() {
+            Some((_, last_end)) if start <= *last_end + 1 => *last_end = end.max(*last_end),
+            _ => blocks.push((start, end)),
+        }
+    }
+    blocks
+}
+
+/// Given a text, its separator, the numbers of the matching lines and the amount of context lines,
+/// returns each line to be printed with its number and kind, and a '--' line between blocks.
+/// Each line of the text appears at most once, in the order of the text.
+///
+fn context_lines(
+    text: &str,
+    separator: char,
+    matches: &[usize],
+    context: usize,
+) -> Vec<(usize, LineKind, String)> {
+    let text_lines: Vec<&str> = split_lines(text, separator).collect();
+    let matching: HashSet<usize> = matches.iter().copied().collect();
+
+    let mut lines = Vec::new();
+    for (i, (start, end)) in context_blocks(matches, text_lines.len(), context)
+        .into_iter()
+        .enumerate()
+    {
+        if i > 0 {
+            lines.push((0, LineKind::BlockSeparator, "--".to_string()));
+        }
+        for number in start..=end {
+            let kind = if matching.contains(&number) {
+                LineKind::Match
+            } else {
+                LineKind::Context
+            };
+            lines.push((number, kind, text_lines[number - 1].to_string()));
+        }
+    }
+    lines
+}
+
+/// Given the Arguments, reads the file and returns the lines to be printed.
+/// If the file is binary, only a notice is returned when the file matches the regex,
+/// unless the text flag is set.
+///
+/// # Arguments
+///
+/// * `arguments` - The Arguments of the program
+///
+/// # Returns
+///
+/// * Vec<String> - The lines to be printed
+/// * String - The error if the file can not be read or the regex is invalid
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::{search_file, Arguments};
+///
+/// let binding = { vec!["rgrep", "pppp", "res/test2.txt"] };
+/// let args = binding.iter().map(|s| s.to_string());
+/// let arguments = Arguments::new(args).unwrap();
+///
+/// let result = search_file(&arguments).unwrap();
+/// assert_eq!(result, vec!["|pppp"]);
+/// ```
+///
+pub fn search_file(arguments: &Arguments) -> Result<Vec<String>, String> {
+    let (compiled, _) = arguments.compiled_pattern()?;
+    let matches = search_path(arguments, &compiled, &arguments.path)?;
+    Ok(matches.output(false, arguments.field_separator()))
+}
+
+/// Given a path of a directory, the current depth, the Arguments and the directories already visited,
+/// returns the paths of all the files inside it, sorted by name.
+/// Subdirectories deeper than the maximum depth are not searched,
+/// and symbolic links to directories are skipped unless the follow symlinks flag is set.
+/// A directory that was already visited, through a link or not, is not searched again to avoid loops.
+///
+fn collect_files(
+    path: &Path,
+    depth: usize,
+    arguments: &Arguments,
+    visited: &mut HashSet<PathBuf>,
+) -> Vec<String> {
+    let is_new = fs::canonicalize(path).is_ok_and(|canonical| visited.insert(canonical));
+    if !is_new {
+        return Vec::new();
+    }
+
+    let mut entries: Vec<fs::DirEntry> = match fs::read_dir(path) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).collect(),
+        Err(_) => return Vec::new(),
+    };
+    entries.sort_by_key(|entry| entry.path());
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry_path = entry.path();
+        let is_symlink = fs::symlink_metadata(&entry_path)
+            .map(|metadata| metadata.file_type().is_symlink())
+            .unwrap_or(false);
+
+        if entry_path.is_dir() {
+            let below_max_depth = arguments.max_depth.is_none_or(|max| depth < max);
+            if (!is_symlink || arguments.follow_symlinks) && below_max_depth {
+                files.extend(collect_files(&entry_path, depth + 1, arguments, visited));
+            }
+        } else {
+            files.push(entry_path.to_string_lossy().into_owned());
+        }
+    }
+    files
+}
+
+/// Given the Arguments, searches the paths and returns the lines to be printed with the stats of the search.
+/// If the recursive flag is set and a path is a directory, all the files inside it are searched.
+/// When more than one file is searched, each line is prefixed with the path of its file
+/// and the errors of each file are collected.
+///
+/// # Arguments
+///
+/// * `arguments` - The Arguments of the program
+///
+/// # Returns
+///
+/// * SearchOutput - The lines to be printed, the errors of the files and the stats of the search
+/// * String - The error if the regex is invalid or the file can not be read
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::{run_search, Arguments};
+///
+/// let binding = { vec!["rgrep", "-r", "regex", "res/dir"] };
+/// let args = binding.iter().map(|s| s.to_string());
+/// let arguments = Arguments::new(args).unwrap();
+///
+/// let output = run_search(&arguments).unwrap();
+/// assert_eq!(output.lines[0], "res/dir/a.txt:regex one");
+/// ```
+///
+pub fn run_search(arguments: &Arguments) -> Result<SearchOutput, String> {
+    let mut output = SearchOutput::default();
+    let is_directory = |path: &str| arguments.recursive && Path::new(path).is_dir();
+
+    let (compiled, pattern_errors) = arguments.compiled_pattern()?;
+    output.errors.extend(pattern_errors);
+    let mut seen = HashSet::new();
+
+    if arguments.paths.len() <= 1 && !is_directory(&arguments.path) && !arguments.match_filename {
+        let mut matches = search_path(arguments, &compiled, &arguments.path)?;
+        if arguments.unique_global {
+            retain_unseen(&mut matches, &mut seen);
+        }
+        output.stats.files_searched = 1;
+        add_matches(
+            &mut output,
+            &matches,
+            arguments.show_filename(false),
+            arguments,
+        );
+        return Ok(output);
+    }
+
+    let with_filename = arguments.show_filename(true);
+
+    for path in &arguments.paths {
+        let files = if is_directory(path) {
+            collect_files(Path::new(path), 0, arguments, &mut HashSet::new())
+        } else {
+            vec![path.clone()]
+        };
+
+        for file in files {
+            output.stats.files_searched += 1;
+            if arguments.match_filename {
+                if compiled.is_match(&file) != arguments.invert {
+                    output.stats.files_matched += 1;
+                    output.stats.matching_lines += 1;
+                    output.lines.push(file);
+                }
+                continue;
+            }
+            match search_path(arguments, &compiled, &file) {
+                Ok(mut matches) => {
+                    if arguments.unique_global {
+                        retain_unseen(&mut matches, &mut seen);
+                    }
+                    add_matches(&mut output, &matches, with_filename, arguments)
+                }
+                Err(err) => output.errors.push(format!("{}: {}", file, err)),
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Given the Arguments and an output, searches the paths as in run_search, writing each line to be printed
+/// to the output as soon as it is found and flushing the output after each line.
+/// Each file, and the standard input, is read one line at a time, so a line is printed before the rest
+/// of the file is read. A binary file is still read whole, since only a notice is printed for it.
+/// The returned output has the errors and the stats of the search, but no lines.
+///
+/// # Arguments
+///
+/// * `arguments` - The Arguments of the program
+/// * `out` - The output where the lines are written
+///
+/// # Returns
+///
+/// * SearchOutput - The errors of the files and the stats of the search
+/// * String - The error if the regex is invalid or the file can not be read
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::{run_search_streamed, Arguments};
+///
+/// let binding = { vec!["rgrep", "--line-buffered", "-n", "e", "res/test2.txt"] };
+/// let args = binding.iter().map(|s| s.to_string());
+/// let arguments = Arguments::parse(args).unwrap();
+///
+/// let mut out = Vec::new();
+/// let output = run_search_streamed(&arguments, &mut out).unwrap();
+/// assert_eq!(out, b"2:ee|oo\n3:eo\n");
+/// assert_eq!(output.stats.matching_lines, 2);
+/// ```
+///
+pub fn run_search_streamed(
+    arguments: &Arguments,
+    out: &mut impl Write,
+) -> Result<SearchOutput, String> {
+    let mut output = SearchOutput::default();
+    let is_directory = |path: &str| arguments.recursive && Path::new(path).is_dir();
+
+    let (compiled, pattern_errors) = arguments.compiled_pattern()?;
+    output.errors.extend(pattern_errors);
+    let mut seen = HashSet::new();
+
+    if arguments.paths.len() <= 1 && !is_directory(&arguments.path) {
+        let with_filename = arguments.show_filename(false);
+        output.stats.files_searched = 1;
+        stream_path(
+            arguments,
+            &compiled,
+            &arguments.path,
+            with_filename,
+            &mut seen,
+            &mut output,
+            out,
+        )?;
+        return Ok(output);
+    }
+
+    let with_filename = arguments.show_filename(true);
+
+    for path in &arguments.paths {
+        let files = if is_directory(path) {
+            collect_files(Path::new(path), 0, arguments, &mut HashSet::new())
+        } else {
+            vec![path.clone()]
+        };
+
+        for file in files {
+            output.stats.files_searched += 1;
+            if let Err(err) = stream_path(
+                arguments,
+                &compiled,
+                &file,
+                with_filename,
+                &mut seen,
+                &mut output,
+                out,
+            ) {
+                output.errors.push(format!("{}: {}", file, err));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Given the Arguments, the compiled pattern, the path of a file, if the lines are prefixed with the path,
+/// the lines found in the previous files, the output of the search and a writer,
+/// searches the file one line at a time and writes each line to be printed as soon as it is found.
+/// The stats of the file are added to the output of the search.
+///
+fn stream_path(
+    arguments: &Arguments,
+    compiled: &CompiledPattern,
+    path: &str,
+    with_filename: bool,
+    seen: &mut HashSet<String>,
+    output: &mut SearchOutput,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let mut reader: Box<dyn BufRead> = if path == STDIN_PATH {
+        Box::new(io::stdin().lock())
+    } else {
+        match fs::File::open(normalize_path(path)) {
+            Ok(file) => Box::new(io::BufReader::new(file)),
+            Err(err) => return Err(process_error(err).message().to_string()),
+        }
+    };
+    let start = match reader.fill_buf() {
+        Ok(start) => start,
+        Err(err) => return Err(process_error(err).message().to_string()),
+    };
+    if arguments.warn_empty && start.is_empty() {
+        return Err(ProgramError::EmptyFile.message().to_string());
+    }
+
+    let separator = arguments.separator();
+    let mut write = |matches: &FileMatches| {
+        let mut printed = SearchOutput::default();
+        add_matches(&mut printed, matches, with_filename, arguments);
+        write_lines_flushed(&printed.lines, separator, out).unwrap_or(());
+    };
+
+    if !arguments.text && !arguments.null_data && is_binary(start) {
+        let bytes = read_bytes(reader).map_err(|err| err.message().to_string())?;
+        let matches = search_bytes(arguments, compiled, path, bytes)?;
+        if matches.matching_count() > 0 {
+            output.stats.files_matched += 1;
+            output.stats.matching_lines += matches.matching_count();
+        }
+        write(&matches);
+        return Ok(());
+    }
+
+    let mut found = HashSet::new();
+    let mut selected = 0;
+    let mut matched = false;
+    let mut bytes = Vec::new();
+    for number in 1.. {
+        if arguments
+            .max_count_per_file
+            .is_some_and(|max| selected >= max)
+        {
+            break;
+        }
+        bytes.clear();
+        match reader.read_until(separator as u8, &mut bytes) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(err) => return Err(process_error(err).message().to_string()),
+        }
+        if bytes.last() == Some(&(separator as u8)) {
+            bytes.pop();
+        }
+        let line = match arguments.encoding {
+            Encoding::Utf8 => match std::str::from_utf8(&bytes) {
+                Ok(line) => line.to_string(),
+                Err(_) => return Err(ProgramError::InvalidFileFormat.message().to_string()),
+            },
+            Encoding::Latin1 => latin1_to_text(&bytes),
+        };
+        if arguments.unique && !arguments.invert && found.contains(&line) {
+            continue;
+        }
+
+        let mut matches = search_text(arguments, compiled, path, &line)?;
+        if matches.matching_count() == 0 {
+            continue;
+        }
+        selected += 1;
+        found.insert(line);
+        if arguments.unique_global {
+            retain_unseen(&mut matches, seen);
+        }
+        matches
+            .numbers
+            .iter_mut()
+            .for_each(|line_number| *line_number = number);
+        matched |= matches.matching_count() > 0;
+        output.stats.matching_lines += matches.matching_count();
+        write(&matches);
+    }
+
+    if matched {
+        output.stats.files_matched += 1;
+    }
+    Ok(())
+}
+
+/// Given the matches of a file and the lines already found in the previous files,
+/// removes the lines that were already found and adds the new ones to the found lines.
+/// Context lines are always kept.
+///
+fn retain_unseen(matches: &mut FileMatches, seen: &mut HashSet<String>) {
+    let keep: Vec<bool> = matches
+        .lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            matches
+                .kinds
+                .get(i)
+                .is_some_and(|kind| *kind != LineKind::Match)
+                || seen.insert(line.clone())
+        })
+        .collect();
+
+    let mut kept = keep.iter();
+    matches.lines.retain(|_| *kept.next().unwrap_or(&true));
+    let mut kept = keep.iter();
+    matches.numbers.retain(|_| *kept.next().unwrap_or(&true));
+    let mut kept = keep.iter();
+    matches.kinds.retain(|_| *kept.next().unwrap_or(&true));
+    let mut kept = keep.iter();
+    matches.columns.retain(|_| *kept.next().unwrap_or(&true));
+}
+
+/// Given the output of a search and the matches of a file, adds the lines and stats of the file to the output.
+/// In count mode only the amount of matching lines of the file is added.
+///
+fn add_matches(
+    output: &mut SearchOutput,
+    matches: &FileMatches,
+    with_filename: bool,
+    arguments: &Arguments,
+) {
+    if matches.matching_count() > 0 {
+        output.stats.files_matched += 1;
+        output.stats.matching_lines += matches.matching_count();
+    }
+
+    let field_separator = arguments.field_separator();
+    if arguments.files_with_matches {
+        if !matches.lines.is_empty() {
+            output.lines.push(matches.path.clone());
+        }
+    } else if arguments.count {
+        output
+            .lines
+            .push(matches.count_output(with_filename, field_separator));
+    } else {
+        let colors = ColorScheme::default();
+        let colors = Some(&colors).filter(|_| arguments.use_color());
+        let lines = matches.output_with_colors(with_filename, field_separator, colors);
+        match arguments.max_line_length {
+            Some(max) => output
+                .lines
+                .extend(lines.iter().map(|line| truncate_line(line, max))),
+            None => output.lines.extend(lines),
+        }
+    }
+}
+
+/// Given a line to be printed and a maximum amount of characters, returns the line truncated with "..."
+/// if it is longer. The escape codes of the colors are kept and not counted,
+/// and the color is reset if the line is cut.
+///
+/// # Arguments
+///
+/// * `line` - The line to be printed
+/// * `max` - The maximum amount of characters of the line
+///
+/// # Returns
+///
+/// * String - The line, truncated if it is longer than the maximum
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::truncate_line;
+///
+/// assert_eq!(truncate_line("abcdef", 3), "abc...");
+/// assert_eq!(truncate_line("abc", 3), "abc");
+/// ```
+///
+pub fn truncate_line(line: &str, max: usize) -> String {
+    let mut truncated = String::new();
+    let mut visible = 0;
+    let mut colored = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            truncated.push(c);
+            for code in chars.by_ref() {
+                truncated.push(code);
+                if code == 'm' {
+                    break;
+                }
+            }
+            colored = true;
+            continue;
+        }
+        if visible == max {
+            if colored {
+                truncated.push_str(COLOR_RESET);
+            }
+            truncated.push_str("...");
+            return truncated;
+        }
+        truncated.push(c);
+        visible += 1;
+    }
+    truncated
+}
+
+/// Given an error while reading a file, returns the corresponding ProgramError.
+/// The error is classified by its kind, so it does not depend on the message of the platform.
+///
+fn process_error(err: io::Error) -> ProgramError {
+    match err.kind() {
+        io::ErrorKind::NotFound => ProgramError::InvalidFilePath,
+        io::ErrorKind::InvalidData => ProgramError::InvalidFileFormat,
+        _ => ProgramError::ErrorWhileReadingFile,
+    }
+}
+
+/// Given a path, returns it without the matching pair of quotes that surround it, if any.
+/// Paths copied from a Windows shell are often quoted, as in "C:\\My Files\\a.txt".
+/// Any other character is kept, since a file name can start or end with spaces.
+///
+fn normalize_path(path: &str) -> &str {
+    for quote in ['"', '\''] {
+        if path.len() >= 2 && path.starts_with(quote) && path.ends_with(quote) {
+            return &path[1..path.len() - 1];
+        }
+    }
+    path
+}
+
+/// Given an error, prints the error
+///
+/// # Arguments
+///
+/// * `err` - A string that represents the error
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::print_error;
+///
+/// print_error("Error while reading file");
+/// ```
+///
+pub fn print_error(err: &str) {
+    write_error(err, &mut std::io::stderr()).unwrap_or(());
+}
+
+/// Given an error and an output, writes the error prefixed with the name of the program
+///
+/// # Arguments
+///
+/// * `err` - A string that represents the error
+/// * `out` - The output where the error is written
+///
+/// # Returns
+///
+/// * io::Error - The error if the output could not be written
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::write_error;
+///
+/// let mut out = Vec::new();
+/// write_error("Invalid file path", &mut out).unwrap();
+///
+/// assert_eq!(out, b"rgrep: Invalid file path\n");
+/// ```
+///
+pub fn write_error<W: Write>(err: &str, out: &mut W) -> io::Result<()> {
+    writeln!(out, "rgrep: {}", err)
+}
+
+/// Given the stats of a search, prints the summary of the search
+///
+/// # Arguments
+///
+/// * `stats` - The stats of the search
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::{print_stats, SearchStats};
+///
+/// print_stats(&SearchStats::default());
+/// ```
+///
+pub fn print_stats(stats: &SearchStats) {
+    writeln!(&mut std::io::stderr(), "{}", stats.summary()).unwrap_or(());
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn verify_correct_arguments() {
+    fn verify_correct_arguments() {
+        let binding = { vec!["rgrep", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+
+        let arguments = Arguments::new(args).unwrap();
+        assert_eq!(arguments.regex, "regex".to_string());
+        assert_eq!(arguments.path, "path".to_string());
+    }
+
+    #[test]
+    fn verify_incorrect_arguments() {
+        let binding1 = { vec!["rgrep", "regex"] };
+        let args1 = binding1.iter().map(|s| s.to_string());
+        let return1 = Arguments::new(args1).unwrap_err();
+        assert_eq!(return1.message(), ProgramError::PathMissing.message());
+
+        let binding2 = { vec!["rgrep", "regex", "path", "extra"] };
+        let args2 = binding2.iter().map(|s| s.to_string());
+        let return2 = Arguments::new(args2).unwrap_err();
+        assert_eq!(
+            return2.message(),
+            ProgramError::InvalidAmountOfArguments.message()
+        );
+
+        let binding3 = { vec!["rgrep"] };
+        let args3 = binding3.iter().map(|s| s.to_string());
+        let return3 = Arguments::new(args3).unwrap_err();
+        assert_eq!(return3.message(), ProgramError::ArgumentMissing.message());
+    }
+
+    #[test]
+    fn try_invalid_file() {
+        let binding1 = { vec!["rgrep", "regex", "res/test-1.txt"] };
+        let args1 = binding1.iter().map(|s| s.to_string());
+        let arguments1 = Arguments::new(args1).unwrap();
+        let text_read1 = read_file(arguments1.path).unwrap_err();
+        assert_eq!(
+            text_read1.message(),
+            ProgramError::InvalidFilePath.message()
+        );
+
+        let binding2 = { vec!["rgrep", "regex", "res/invalid_format.txt"] };
+        let args2 = binding2.iter().map(|s| s.to_string());
+        let arguments2 = Arguments::new(args2).unwrap();
+        let text_read2 = read_file(arguments2.path).unwrap_err();
+        assert_eq!(
+            text_read2.message(),
+            ProgramError::InvalidFileFormat.message()
+        );
+    }
+
+    #[test]
+    fn try_valid_file_relative_path() {
+        let binding = { vec!["rgrep", "regex", "res/test0.txt"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::new(args).unwrap();
+        let text_read = read_file(arguments.path).unwrap();
+        let result = run_rgrep(&arguments.regex, &text_read).is_ok();
+        assert!(result);
+    }
+
+    #[test]
+    fn verify_unknown_flag() {
+        let binding = { vec!["rgrep", "--frobnicate", "pattern", "file"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::parse(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::UnknownFlag.message());
+
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::new(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::UnknownFlag.message());
+
+        let binding = { vec!["rgrep", "pattern", "-", "--", "--frobnicate"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert_eq!(arguments.paths, vec!["-", "--frobnicate"]);
+    }
+
+    #[test]
+    fn verify_max_depth_flag() {
+        let binding = { vec!["rgrep", "-r", "--max-depth", "2", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::new(args).unwrap();
+        assert_eq!(arguments.max_depth, Some(2));
+        assert_eq!(arguments.regex, "regex".to_string());
+
+        let binding = { vec!["rgrep", "--max-depth", "two", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::new(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::InvalidFlagValue.message());
+
+        let binding = { vec!["rgrep", "--max-depth"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::new(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::InvalidFlagValue.message());
+    }
+
+    #[test]
+    fn verify_parse_flag_orderings() {
+        let orderings = [
+            vec!["rgrep", "-r", "-z", "regex", "path"],
+            vec!["rgrep", "regex", "-r", "path", "-z"],
+            vec!["rgrep", "-z", "regex", "path", "-r"],
+        ];
+
+        for binding in orderings {
+            let args = binding.iter().map(|s| s.to_string());
+            let arguments = Arguments::parse(args).unwrap();
+            assert!(arguments.recursive);
+            assert!(arguments.null_data);
+            assert_eq!(arguments.regex, "regex".to_string());
+            assert_eq!(arguments.paths, vec!["path"]);
+        }
+
+        let binding = { vec!["rgrep", "regex", "path1", "--max-depth", "1", "path2"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert_eq!(arguments.max_depth, Some(1));
+        assert_eq!(arguments.path, "path1".to_string());
+        assert_eq!(arguments.paths, vec!["path1", "path2"]);
+    }
+
+    #[test]
+    fn verify_parse_terminator() {
+        let binding = { vec!["rgrep", "-r", "--", "-z", "--stats"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert!(arguments.recursive);
+        assert!(!arguments.null_data);
+        assert!(!arguments.stats);
+        assert_eq!(arguments.regex, "-z".to_string());
+        assert_eq!(arguments.paths, vec!["--stats"]);
+
+        let binding = { vec!["rgrep", "-r", "--", "regex"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::parse(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::PathMissing.message());
+
+        let binding = { vec!["rgrep", "-r"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::parse(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::ArgumentMissing.message());
+    }
+
+    #[test]
+    fn verify_parse_many_patterns() {
+        let binding = { vec!["rgrep", "-e", "abc", "path1", "--regexp", "def", "path2"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert_eq!(arguments.patterns, vec!["abc", "def"]);
+        assert_eq!(arguments.paths, vec!["path1", "path2"]);
+        assert_eq!(arguments.pattern(), "abc|def");
+
+        let binding = { vec!["rgrep", "-F", "-e", "a|b", "-e", "c.d", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::new(args).unwrap();
+        assert_eq!(arguments.paths, vec!["path"]);
+        assert_eq!(arguments.pattern(), "a\\|b|c\\.d");
+
+        let binding = { vec!["rgrep", "-e", "abc"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::parse(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::PathMissing.message());
+
+        let binding = { vec!["rgrep", "path", "-e"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::parse(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::InvalidFlagValue.message());
+    }
+
+    #[test]
+    fn verify_parse_pattern_file() {
+        let binding = { vec!["rgrep", "-f", "res/pattern_list.txt", "-e", "ghi", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert_eq!(arguments.patterns, vec!["abc", "d.f", "ghi"]);
+        assert_eq!(arguments.paths, vec!["path"]);
+
+        let binding = { vec!["rgrep", "-f", "res/empty.txt", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert!(arguments.patterns.is_empty());
+        assert_eq!(arguments.paths, vec!["path"]);
+
+        let binding = { vec!["rgrep", "-f", "res/missing.txt", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::parse(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::InvalidFilePath.message());
+    }
+
+    #[test]
+    fn test_split_alternatives() {
+        assert_eq!(split_alternatives("abc|def"), vec!["abc", "def"]);
+        assert_eq!(split_alternatives("a\\|b|c"), vec!["a\\|b", "c"]);
+        assert_eq!(split_alternatives("[a|b]|(c|d)"), vec!["[a|b]", "(c|d)"]);
+        assert_eq!(
+            split_alternatives("[[:digit:]|]|e"),
+            vec!["[[:digit:]|]", "e"]
+        );
+        assert_eq!(split_alternatives("|a|"), vec!["", "a", ""]);
+        assert_eq!(split_alternatives("[]|a]|b"), vec!["[]|a]", "b"]);
+        assert_eq!(split_alternatives("[^]|a]|x"), vec!["[^]|a]", "x"]);
+    }
+
+    #[test]
+    fn test_valid_alternatives_with_leading_bracket() {
+        assert_eq!(valid_alternatives("[]|a]"), ("[]|a]".to_string(), vec![]));
+        assert_eq!(
+            valid_alternatives("[^]|a]|x"),
+            ("[^]|a]|x".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn verify_color_flag() {
+        let modes = [
+            ("--color=always", ColorMode::Always),
+            ("--color=never", ColorMode::Never),
+            ("--color=auto", ColorMode::Auto),
+            ("--color", ColorMode::Auto),
+        ];
+
+        for (flag, mode) in modes {
+            let binding = { vec!["rgrep", flag, "regex", "path"] };
+            let args = binding.iter().map(|s| s.to_string());
+            assert_eq!(Arguments::new(args).unwrap().color, mode);
+        }
+
+        let binding = { vec!["rgrep", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        assert_eq!(Arguments::parse(args).unwrap().color, ColorMode::Never);
+
+        let binding = { vec!["rgrep", "--color=sometimes", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::parse(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::InvalidFlagValue.message());
+    }
+
+    #[test]
+    fn verify_sort_flag() {
+        let modes = [
+            ("--sort", SortMode::Alphabetic),
+            ("--sort=alphabetic", SortMode::Alphabetic),
+            ("--sort=numeric", SortMode::Numeric),
+        ];
+
+        for (flag, mode) in modes {
+            let binding = { vec!["rgrep", "regex", flag, "path"] };
+            let args = binding.iter().map(|s| s.to_string());
+            assert_eq!(Arguments::parse(args).unwrap().sort, mode);
+        }
+
+        let binding = { vec!["rgrep", "--sort=random", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::parse(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::InvalidFlagValue.message());
+    }
+
+    #[test]
+    fn test_sort_lines() {
+        let matches = ["banana 2", "apple 10", "cherry 1", "apple 10"];
+
+        let mut lines: Vec<String> = matches.iter().map(|s| s.to_string()).collect();
+        sort_lines(&mut lines, SortMode::Unsorted);
+        assert_eq!(lines, matches);
+
+        sort_lines(&mut lines, SortMode::Alphabetic);
+        assert_eq!(lines, vec!["apple 10", "apple 10", "banana 2", "cherry 1"]);
+
+        let mut lines: Vec<String> = ["12:b", "-3:c", "2.5:a", "12:a", "none"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        sort_lines(&mut lines, SortMode::Numeric);
+        assert_eq!(lines, vec!["-3:c", "none", "2.5:a", "12:a", "12:b"]);
+    }
+
+    #[test]
+    fn test_truncate_line() {
+        assert_eq!(truncate_line("", 0), "");
+        assert_eq!(truncate_line("ab", 0), "...");
+        assert_eq!(truncate_line("caf\u{e9} latte", 4), "caf\u{e9}...");
+
+        let highlighted = format!("a{}bc{}de", COLOR_MATCH, COLOR_RESET);
+        assert_eq!(
+            truncate_line(&highlighted, 2),
+            format!("a{}b{}...", COLOR_MATCH, COLOR_RESET)
+        );
+        assert_eq!(truncate_line(&highlighted, 5), highlighted);
+    }
+
+    #[test]
+    fn verify_field_separator_flag() {
+        let binding = { vec!["rgrep", "-n", "--field-separator", "\t", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert!(arguments.line_number);
+        assert_eq!(arguments.field_separator(), "\t");
+
         let binding = { vec!["rgrep", "regex", "path"] };
         let args = binding.iter().map(|s| s.to_string());
+        assert_eq!(Arguments::parse(args).unwrap().field_separator(), ":");
+
+        let binding = { vec!["rgrep", "regex", "path", "--field-separator"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::parse(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::InvalidFlagValue.message());
+    }
+
+    #[test]
+    fn test_file_matches_output_prefixes() {
+        let matches = FileMatches {
+            path: "file.txt".to_string(),
+            lines: vec!["abc".to_string()],
+            numbers: vec![3],
+            kinds: Vec::new(),
+            columns: Vec::new(),
+            count: None,
+            binary: false,
+        };
+
+        assert_eq!(matches.output(false, "\t"), vec!["3\tabc"]);
+        assert_eq!(matches.output(true, ":"), vec!["file.txt:3:abc"]);
+        assert_eq!(matches.count_output(true, "\t"), "file.txt\t1");
+    }
+
+    #[test]
+    fn test_limit_matches_keeps_trailing_context() {
+        let text = "one\nmatch a\ntwo\nthree\nfour\nfive\nmatch b\nsix\n";
+        let with_context = context_lines(text, '\n', &[2, 7], 1);
+        let mut numbers: Vec<usize> = with_context.iter().map(|(number, _, _)| *number).collect();
+        let mut kinds: Vec<LineKind> = with_context.iter().map(|(_, kind, _)| *kind).collect();
+        let mut lines: Vec<String> = with_context.into_iter().map(|(_, _, line)| line).collect();
+
+        limit_matches(&mut lines, &mut numbers, &mut kinds, 1);
+        assert_eq!(lines, vec!["one", "match a", "two"]);
+        assert_eq!(numbers, vec![1, 2, 3]);
+
+        let mut lines = vec!["a".to_string(), "b".to_string()];
+        limit_matches(&mut lines, &mut Vec::new(), &mut Vec::new(), 0);
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_file_matches_output_colored_prefixes() {
+        let matches = FileMatches {
+            path: "file.txt".to_string(),
+            lines: vec!["abc".to_string()],
+            numbers: vec![3],
+            columns: vec![2],
+            ..Default::default()
+        };
+        let colors = ColorScheme::default();
+
+        assert_eq!(
+            matches.output_with_colors(true, ":", Some(&colors)),
+            vec![format!(
+                "{}file.txt{}:{}3{}:{}2{}:abc",
+                COLOR_FILENAME,
+                COLOR_RESET,
+                COLOR_LINE_NUMBER,
+                COLOR_RESET,
+                COLOR_LINE_NUMBER,
+                COLOR_RESET
+            )]
+        );
+        assert_eq!(
+            matches.output_with_colors(true, ":", None),
+            matches.output(true, ":")
+        );
+    }
+
+    #[test]
+    fn test_context_lines_merge_overlapping_windows() {
+        let text = "one\nmatch a\ntwo\nmatch b\nthree\nfour\nfive\nmatch c\n";
+
+        let lines = context_lines(text, '\n', &[2, 4, 8], 1);
+        let numbers: Vec<usize> = lines.iter().map(|(number, _, _)| *number).collect();
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5, 0, 7, 8]);
+        assert_eq!(lines[1].1, LineKind::Match);
+        assert_eq!(lines[2].1, LineKind::Context);
+        assert_eq!(lines[5], (0, LineKind::BlockSeparator, "--".to_string()));
+
+        let lines = context_lines(text, '\n', &[2, 4], 5);
+        assert_eq!(lines.len(), 8);
+    }
+
+    #[test]
+    fn verify_help_and_version_flags() {
+        let binding = { vec!["rgrep", "--help"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert!(arguments.help);
+        assert!(arguments.path.is_empty());
 
+        let binding = { vec!["rgrep", "--version"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::new(args).unwrap();
+        assert!(arguments.version);
+        assert!(arguments.paths.is_empty());
+
+        let binding = { vec!["rgrep", "regex", "res/missing.txt", "--help"] };
+        let args = binding.iter().map(|s| s.to_string());
+        assert!(Arguments::parse(args).unwrap().help);
+
+        assert_eq!(version(), format!("rgrep {}", env!("CARGO_PKG_VERSION")));
+        assert!(usage().contains("--help"));
+    }
+
+    #[test]
+    fn verify_null_data_flag() {
+        let binding = { vec!["rgrep", "-z", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
         let arguments = Arguments::new(args).unwrap();
+        assert!(arguments.null_data);
         assert_eq!(arguments.regex, "regex".to_string());
         assert_eq!(arguments.path, "path".to_string());
+
+        let binding = { vec!["rgrep", "--null", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        assert!(Arguments::new(args).unwrap().null_data);
+
+        let binding = { vec!["rgrep", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        assert!(!Arguments::new(args).unwrap().null_data);
     }
 
     #[test]
-    fn verify_incorrect_arguments() {
-        let binding1 = { vec!["rgrep", "regex"] };
-        let args1 = binding1.iter().map(|s| s.to_string());
-        let return1 = Arguments::new(args1).unwrap_err();
-        assert_eq!(return1.message(), ProgramError::PathMissing.message());
+    fn test_null_separated_lines() {
+        let text = "abc\0a\nb\0xyz\0bc".to_string();
 
-        let binding2 = { vec!["rgrep", "regex", "path", "extra"] };
-        let args2 = binding2.iter().map(|s| s.to_string());
-        let return2 = Arguments::new(args2).unwrap_err();
+        let result = run_rgrep_separated("b", &text, '\0').unwrap();
+        assert_eq!(result, vec!["abc", "a\nb", "bc"]);
+
+        let mut out = Vec::new();
+        write_lines(&result, '\0', &mut out).unwrap();
+        assert_eq!(out, b"abc\0a\nb\0bc\0");
+    }
+
+    #[test]
+    fn test_multiline_regions() {
+        let text = "start line\nend line\nanother start".to_string();
+
+        let result = run_rgrep_multiline("line[[:space:]]end", &text, false).unwrap();
+        assert_eq!(result, vec!["line\nend"]);
+
+        let result = run_rgrep_multiline("^[[:alpha:]]+", &text, false).unwrap();
+        assert_eq!(result, vec!["start", "end", "another"]);
+
+        let result = run_rgrep_multiline("start.*end", &text, false).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_multiline_dotall() {
+        let text = "a\nb".to_string();
+
+        let result = run_rgrep_multiline("a.b", &text, false).unwrap();
+        assert!(result.is_empty());
+
+        let result = run_rgrep_multiline("a.b", &text, true).unwrap();
+        assert_eq!(result, vec!["a\nb"]);
+
+        let binding = { vec!["rgrep", "--multiline", "-s", "a.b", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::new(args).unwrap();
+        assert!(arguments.multiline);
+        assert!(arguments.dotall);
+    }
+
+    #[test]
+    fn test_run_rgrep_escaped_pipe_in_alternatives() {
+        let text = "z\nq|\na||b\nc.\ncx\na\\";
+
+        let result = run_rgrep("z|q\\|", text).unwrap();
+        assert_eq!(result, vec!["z", "q|"]);
+
+        let result = run_rgrep("a\\|\\|b|c\\.", text).unwrap();
+        assert_eq!(result, vec!["a||b", "c."]);
+    }
+
+    #[test]
+    fn test_run_rgrep_escaped_pipe() {
+        let text = "a|b\na\\\nb\nab".to_string();
+
+        let result = run_rgrep("a\\|b", &text).unwrap();
+        assert_eq!(result, vec!["a|b"]);
+
+        let result = run_rgrep("a\\\\|b", &text).unwrap();
+        assert_eq!(result, vec!["a|b", "a\\", "b", "ab"]);
+    }
+
+    #[test]
+    fn test_run_rgrep_end_anchor_per_alternative() {
+        let text = "a$b\nba\nab\nxb";
+
+        let result = run_rgrep("a$|b", text).unwrap();
+        assert_eq!(result, vec!["a$b", "ba", "ab", "xb"]);
+
+        let result = run_rgrep("a$|x$", text).unwrap();
+        assert_eq!(result, vec!["ba"]);
+
+        let result = run_rgrep("a$b|zz", text).unwrap();
+        assert_eq!(result, vec!["a$b"]);
+    }
+
+    #[test]
+    fn test_count_total_matches() {
+        let text = "aaa\nba".to_string();
+
+        let total = count_total_matches("a", &text).unwrap();
+        assert_eq!(total, 4);
+
+        let total = count_total_matches("z", &text).unwrap();
+        assert_eq!(total, 0);
+    }
+
+    #[test]
+    fn test_count_total_matches_alternatives() {
+        let text = "abcab\nbca\nzzz".to_string();
+
+        let total = count_total_matches("ab|c", &text).unwrap();
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn test_matches_per_line() {
+        let text = "aaa\nb\nba".to_string();
+
+        let counts = matches_per_line("a", &text).unwrap();
+        assert_eq!(counts, vec![(1, 3), (3, 1)]);
+
+        let counts = matches_per_line("z", &text).unwrap();
+        assert!(counts.is_empty());
+
+        let counts = matches_per_line("é", "café\nné é").unwrap();
+        assert_eq!(counts, vec![(1, 1), (2, 2)]);
+
+        let counts = matches_per_line("a*", "aaa\nee|oo\n").unwrap();
+        assert_eq!(counts, vec![(1, 1), (2, 0)]);
+    }
+
+    #[test]
+    fn test_run_rgrep_iter_same_as_run_rgrep() {
+        let text = "abc\nxyz\nab\nzzz\nbc";
+
+        let lazy: Vec<String> = run_rgrep_iter("ab|z", text)
+            .map(|line| line.unwrap())
+            .collect();
+        assert_eq!(lazy, run_rgrep("ab|z", text).unwrap());
+    }
+
+    #[test]
+    fn test_run_rgrep_iter_stops_early() {
+        let text = "ab1\nab2\nab3\nab\u{e9}";
+
+        let first: Vec<Result<String, String>> = run_rgrep_iter("ab", text).take(2).collect();
+        assert_eq!(first, vec![Ok("ab1".to_string()), Ok("ab2".to_string())]);
+
+        assert!(run_rgrep("ab", text).is_err());
+        assert!(run_rgrep_iter("ab", text).any(|line| line.is_err()));
+    }
+
+    #[test]
+    fn test_run_rgrep_iter_invalid_regex() {
+        let mut lines = run_rgrep_iter("a{2", "aa");
+
+        assert!(lines.next().unwrap().is_err());
+        assert!(lines.next().is_none());
+    }
+
+    /// Output that records the length of the written bytes on each flush
+    #[test]
+    fn test_run_rgrep_same_output_as_compiling_per_line() {
+        let text = "abcd\nabecd\nab10cd\nxyz\nab|cd\n";
+
+        for pattern in ["ab.cd", "ab.*cd", "^x|e", "[[:digit:]]+"] {
+            let expected: Vec<String> = text
+                .strip_suffix('\n')
+                .unwrap()
+                .split('\n')
+                .filter(|line| Regex::new(pattern).unwrap().evaluate(line).unwrap().result)
+                .map(|line| line.to_string())
+                .collect();
+
+            assert_eq!(run_rgrep(pattern, text).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_write_error() {
+        let mut out = Vec::new();
+        write_error(ProgramError::InvalidFilePath.message(), &mut out).unwrap();
+        write_error("second error", &mut out).unwrap();
+
+        let written = String::from_utf8(out).unwrap();
+        assert_eq!(written, "rgrep: Invalid file path\nrgrep: second error\n");
+    }
+
+    #[test]
+    fn test_run_search_streamed_same_as_run_search() {
+        let flags: [&[&str]; 8] = [
+            &[],
+            &["-n"],
+            &["-v", "-n"],
+            &["-o", "--column"],
+            &["--unique", "-H"],
+            &["--max-count-per-file", "1"],
+            &["--unique-global", "res/test2.txt"],
+            &["--color=always", "-i"],
+        ];
+
+        for flags in flags {
+            let mut binding = vec!["rgrep", "--line-buffered"];
+            binding.extend(flags);
+            binding.extend(["e|A", "res/test2.txt", "res/duplicates.txt", "nope.txt"]);
+            let args = binding.iter().map(|s| s.to_string());
+            let arguments = Arguments::parse(args).unwrap();
+            assert!(arguments.streams_lines());
+
+            let expected = run_search(&arguments).unwrap();
+            let mut out = Vec::new();
+            let streamed = run_search_streamed(&arguments, &mut out).unwrap();
+
+            let mut printed = Vec::new();
+            write_lines(&expected.lines, '\n', &mut printed).unwrap();
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                String::from_utf8(printed).unwrap()
+            );
+            assert_eq!(streamed.errors, expected.errors);
+            assert_eq!(streamed.stats, expected.stats);
+        }
+    }
+
+    #[test]
+    fn verify_files_from_flag() {
+        let binding = { vec!["rgrep", "--files-from", "res/file_list.txt", "regex"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::new(args).unwrap();
+        assert_eq!(arguments.path, "res/samples/first.txt");
         assert_eq!(
-            return2.message(),
-            ProgramError::InvalidAmountOfArguments.message()
+            arguments.paths,
+            vec!["res/samples/first.txt", "res/samples/second.txt"]
         );
 
-        let binding3 = { vec!["rgrep"] };
-        let args3 = binding3.iter().map(|s| s.to_string());
-        let return3 = Arguments::new(args3).unwrap_err();
-        assert_eq!(return3.message(), ProgramError::ArgumentMissing.message());
+        let binding = {
+            vec![
+                "rgrep",
+                "--files-from",
+                "res/file_list.txt",
+                "regex",
+                "res/test2.txt",
+            ]
+        };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::new(args).unwrap();
+        assert_eq!(arguments.path, "res/test2.txt");
+        assert_eq!(arguments.paths.len(), 3);
+
+        let binding = { vec!["rgrep", "regex"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let error = Arguments::new(args).unwrap_err();
+        assert_eq!(error.message(), ProgramError::PathMissing.message());
     }
 
     #[test]
-    fn try_invalid_file() {
-        let binding1 = { vec!["rgrep", "regex", "res/test-1.txt"] };
-        let args1 = binding1.iter().map(|s| s.to_string());
-        let arguments1 = Arguments::new(args1).unwrap();
-        let text_read1 = read_file(arguments1.path).unwrap_err();
+    fn verify_line_buffered_flag() {
+        let binding = { vec!["rgrep", "--line-buffered", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        assert!(Arguments::parse(args).unwrap().line_buffered);
+
+        let binding = { vec!["rgrep", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        assert!(!Arguments::parse(args).unwrap().line_buffered);
+    }
+
+    #[test]
+    fn test_read_text_from_reader() {
+        let reader = io::Cursor::new(b"regex one\nnothing\n".to_vec());
+        let text = read_text(reader).unwrap();
+        assert_eq!(text, "regex one\nnothing\n");
+    }
+
+    #[test]
+    fn test_read_text_invalid_utf8() {
+        let reader = io::Cursor::new(b"abc\xFF\xFE\n".to_vec());
+        let err = read_text(reader).unwrap_err();
+        assert_eq!(err.message(), ProgramError::InvalidFileFormat.message());
+    }
+
+    #[test]
+    fn verify_stdin_path() {
+        let binding = { vec!["rgrep", "regex", "-"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert_eq!(arguments.path, STDIN_PATH);
+        assert_eq!(arguments.paths, vec![STDIN_PATH.to_string()]);
+    }
+
+    #[test]
+    fn test_run_rgrep_duplicate_lines() {
+        let text = "regex\nother\nregex\nregex two";
+
+        let result = run_rgrep("regex", text).unwrap();
+        assert_eq!(result, vec!["regex", "regex", "regex two"]);
+
+        let result = run_rgrep_numbered("regex", text, '\n').unwrap();
+        let numbers: Vec<usize> = result.into_iter().map(|(number, _)| number).collect();
+        assert_eq!(numbers, vec![1, 3, 4]);
+    }
+
+    #[test]
+    fn test_run_rgrep_unique() {
+        let text = "regex\nother\nregex\nregex two";
+
+        let result = run_rgrep_unique("regex|two", text, '\n').unwrap();
         assert_eq!(
-            text_read1.message(),
-            ProgramError::InvalidFilePath.message()
+            result,
+            vec![(1, "regex".to_string()), (4, "regex two".to_string())]
         );
+    }
 
-        let binding2 = { vec!["rgrep", "regex", "res/invalid_format.txt"] };
-        let args2 = binding2.iter().map(|s| s.to_string());
-        let arguments2 = Arguments::new(args2).unwrap();
-        let text_read2 = read_file(arguments2.path).unwrap_err();
+    #[test]
+    fn verify_unique_flag() {
+        let binding = { vec!["rgrep", "--unique", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        assert!(Arguments::parse(args).unwrap().unique);
+    }
+
+    #[test]
+    fn test_run_rgrep_alternation_inside_group() {
+        let text = "gray\ngrey\ngriy\ngr|ey";
+
+        let result = run_rgrep("gr(a|e)y", text).unwrap();
+        assert_eq!(result, vec!["gray", "grey"]);
+    }
+
+    #[test]
+    fn test_tsv_lines() {
+        let text = "a1 b2\nno digits\n12\\3\n";
+
+        let result = tsv_lines("[[:digit:]]", text).unwrap();
+        assert_eq!(result, vec!["1\t2\ta1 b2", "3\t3\t12\\\\3"]);
+
+        let result = tsv_lines("^", "ab\ncd\n").unwrap();
+        assert_eq!(result, vec!["1\t0\tab", "2\t0\tcd"]);
+
+        assert_eq!(escape_tsv("a\tb\nc\rd"), "a\\tb\\nc\\rd");
+    }
+
+    #[test]
+    fn verify_incompatible_flags() {
+        let combinations = [
+            ["-o", "-v"],
+            ["-o", "-c"],
+            ["-l", "-o"],
+            ["-l", "--count"],
+            ["--multiline", "--invert-match"],
+            ["--tsv", "-o"],
+            ["--tsv", "-c"],
+            ["-v", "--tsv"],
+            ["--tsv", "--multiline"],
+        ];
+
+        for [first, second] in combinations {
+            let binding = { vec!["rgrep", first, second, "regex", "path"] };
+            let args = binding.iter().map(|s| s.to_string());
+            let err = Arguments::parse(args).unwrap_err();
+            assert_eq!(err.message(), ProgramError::IncompatibleFlags.message());
+
+            let args = binding.iter().map(|s| s.to_string());
+            let err = Arguments::new(args).unwrap_err();
+            assert_eq!(err.message(), ProgramError::IncompatibleFlags.message());
+        }
+
+        let binding = { vec!["rgrep", "-o", "-n", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        assert!(Arguments::parse(args).is_ok());
+    }
+
+    #[test]
+    fn test_run_rgrep_progress_same_results() {
+        let text: String = (0..25000)
+            .map(|number| format!("line {}\n", number))
+            .collect();
+
+        let mut progress = Vec::new();
+        let result = run_rgrep_progress("line 1.*7$", &text, '\n', &mut progress).unwrap();
         assert_eq!(
-            text_read2.message(),
-            ProgramError::InvalidFileFormat.message()
+            result,
+            run_rgrep_numbered("line 1.*7$", &text, '\n').unwrap()
         );
+
+        let progress = String::from_utf8(progress).unwrap();
+        let reports: Vec<&str> = progress.split('\r').skip(1).collect();
+        assert_eq!(reports.len(), 3);
+        assert!(reports[0].ends_with("% (10000 lines)"));
+        assert!(reports[1].ends_with("% (20000 lines)"));
+        assert_eq!(reports[2], "100% (25000 lines)\n");
     }
 
     #[test]
-    fn try_valid_file_relative_path() {
-        let binding = { vec!["rgrep", "regex", "res/test0.txt"] };
+    fn verify_progress_flag() {
+        let binding = { vec!["rgrep", "--progress", "regex", "path"] };
         let args = binding.iter().map(|s| s.to_string());
-        let arguments = Arguments::new(args).unwrap();
-        let text_read = read_file(arguments.path).unwrap();
-        let result = run_rgrep(arguments.regex, text_read).is_ok();
-        assert!(result);
+        let arguments = Arguments::parse(args).unwrap();
+        assert!(arguments.progress);
+    }
+
+    #[test]
+    fn test_compiled_pattern_reused() {
+        let pattern = CompiledPattern::new("regex|two$").unwrap();
+
+        let first = pattern
+            .matching_lines("regex one\nnothing\ntwo", '\n')
+            .unwrap();
+        assert_eq!(
+            first,
+            vec![(1, "regex one".to_string()), (3, "two".to_string())]
+        );
+
+        let second = pattern.matching_lines("two three\nno regex", '\n').unwrap();
+        assert_eq!(second, vec![(2, "no regex".to_string())]);
+
+        assert!(CompiledPattern::new("a{").is_err());
+    }
+
+    #[test]
+    fn test_read_file_absolute_path() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("res")
+            .join("test2.txt");
+        let path = path.to_string_lossy().to_string();
+
+        let text = read_file(path.clone()).unwrap();
+        assert_eq!(text, "aaa\nee|oo\neo\nqqqq|\n|pppp\n");
+
+        let text = read_file(format!("\"{}\"", path)).unwrap();
+        assert_eq!(text, "aaa\nee|oo\neo\nqqqq|\n|pppp\n");
+
+        let missing = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("res")
+            .join("missing.txt");
+        let err = read_file(missing.to_string_lossy().to_string()).unwrap_err();
+        assert_eq!(err.message(), ProgramError::InvalidFilePath.message());
+    }
+
+    #[test]
+    fn test_read_file_windows_path() {
+        let result = read_file("res\\test2.txt".to_string());
+        if cfg!(windows) {
+            assert_eq!(result.unwrap(), "aaa\nee|oo\neo\nqqqq|\n|pppp\n");
+        } else {
+            let err = result.unwrap_err();
+            assert_eq!(err.message(), ProgramError::InvalidFilePath.message());
+        }
+
+        let err = read_file("C:\\missing\\file.txt".to_string()).unwrap_err();
+        assert_eq!(err.message(), ProgramError::InvalidFilePath.message());
+
+        let err = read_file("'res/invalid_format.txt'".to_string()).unwrap_err();
+        assert_eq!(err.message(), ProgramError::InvalidFileFormat.message());
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path("\"C:\\My Files\\a.txt\""),
+            "C:\\My Files\\a.txt"
+        );
+        assert_eq!(normalize_path("'res/a.txt'"), "res/a.txt");
+        assert_eq!(normalize_path("res/a.txt"), "res/a.txt");
+        assert_eq!(normalize_path("\""), "\"");
+        assert_eq!(normalize_path(" a.txt"), " a.txt");
+        assert_eq!(normalize_path("\" a.txt \""), " a.txt ");
+        assert_eq!(normalize_path("'a.txt\""), "'a.txt\"");
+    }
+
+    #[test]
+    fn verify_encoding_flag() {
+        let binding = { vec!["rgrep", "--encoding", "latin1", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert_eq!(arguments.encoding, Encoding::Latin1);
+        assert!(arguments.regex_options().unicode);
+
+        let binding = { vec!["rgrep", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+        assert_eq!(arguments.encoding, Encoding::Utf8);
+        assert!(arguments.regex_options().unicode);
+
+        let binding = { vec!["rgrep", "--encoding", "utf16", "regex", "path"] };
+        let args = binding.iter().map(|s| s.to_string());
+        let err = Arguments::parse(args).unwrap_err();
+        assert_eq!(err.message(), ProgramError::InvalidFlagValue.message());
     }
 }
@@ -1,6 +1,7 @@
 // WELCOME TO RGREP: RUSTIC GREP
 // Made by: Gian Luca Spagnolo
 use std::env;
+use std::process;
 
 use rgrep::Arguments;
 use rgrep::*;
@@ -8,24 +9,40 @@ use rgrep::*;
 fn main() {
     let args = env::args_os().map(|arg| arg.to_string_lossy().into_owned());
 
-    match Arguments::new(args) {
+    match Arguments::parse(args) {
+        Ok(arguments) if arguments.help => print_lines(vec![usage()]),
+        Ok(arguments) if arguments.version => print_lines(vec![version()]),
         Ok(arguments) => {
-            let file_text = read_file(arguments.path);
+            let program_output = if arguments.streams_lines() {
+                run_search_streamed(&arguments, &mut std::io::stdout())
+            } else {
+                run_search(&arguments)
+            };
 
-            if let Err(err) = file_text {
-                print_error(err.message());
-            } else if let Ok(text) = file_text {
-                let program_output = run_rgrep(arguments.regex, text);
+            if let Ok(mut output) = program_output {
+                let exit_code = output.exit_code();
+                sort_lines(&mut output.lines, arguments.sort);
+                for error in &output.errors {
+                    print_error(error);
+                }
+                if arguments.line_buffered {
+                    print_lines_flushed(output.lines, arguments.separator());
+                } else {
+                    print_lines_separated(output.lines, arguments.separator());
+                }
 
-                if let Ok(output) = program_output {
-                    print_lines(output);
-                } else if let Err(error) = program_output {
-                    print_error(&error);
+                if arguments.stats {
+                    print_stats(&output.stats);
                 }
+                process::exit(exit_code);
+            } else if let Err(error) = program_output {
+                print_error(&error);
+                process::exit(EXIT_ERROR);
             }
         }
         Err(err) => {
             print_error(err.message());
+            process::exit(EXIT_ERROR);
         }
     }
 }
@@ -3,9 +3,13 @@ pub enum ProgramError {
     ArgumentMissing,
     PathMissing,
     InvalidAmountOfArguments,
+    InvalidFlagValue,
+    IncompatibleFlags,
+    UnknownFlag,
     InvalidFilePath,
     InvalidFileFormat,
     ErrorWhileReadingFile,
+    EmptyFile,
 }
 
 impl ProgramError {
@@ -31,10 +35,14 @@ impl ProgramError {
             ProgramError::ArgumentMissing => "Invalid arguments: regex and path missing",
             ProgramError::PathMissing => "Invalid arguments: path missing",
             ProgramError::InvalidAmountOfArguments => "Invalid amount of arguments",
+            ProgramError::InvalidFlagValue => "Invalid arguments: invalid flag value",
+            ProgramError::IncompatibleFlags => "Invalid arguments: incompatible flags",
+            ProgramError::UnknownFlag => "Invalid arguments: unknown flag",
             // File Reading Errors
             ProgramError::InvalidFilePath => "Invalid file path",
             ProgramError::InvalidFileFormat => "Invalid file format",
             ProgramError::ErrorWhileReadingFile => "An error occurred while reading file",
+            ProgramError::EmptyFile => "The file is empty",
         }
     }
 }
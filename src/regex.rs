@@ -1,15 +1,29 @@
-use std::{collections::VecDeque, str::Chars};
+use crate::split_lines;
+use std::str::Chars;
 
+pub mod regex_builder;
+pub mod regex_char_set;
 pub mod regex_class;
 pub mod regex_error;
+pub mod regex_options;
 pub mod regex_rep;
 pub mod regex_val;
 
-use regex_class::determinate_regex_class;
+use regex_char_set::CharSet;
+use regex_class::{determinate_regex_class, RegexClass};
 use regex_error::RegexError;
+use regex_options::RegexOptions;
 use regex_rep::RegexRep;
 use regex_val::RegexVal;
 
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+
+const NON_ASCII_BYTES_START: u32 = 0xF700;
+const METACHARACTERS: [char; 14] = [
+    '\\', '.', '*', '+', '?', '{', '}', '[', ']', '(', ')', '^', '$', '|',
+];
+
 #[derive(Debug, Clone)]
 pub struct RegexStep {
     pub val: RegexVal,
@@ -18,22 +32,114 @@ pub struct RegexStep {
     pub anchoring_end: bool,
 }
 
-#[derive(Debug, Clone)]
-pub struct EvaluatedStep {
-    step: RegexStep,
-    match_size: usize,
-    backtrackable: bool,
-}
-
 #[derive(Debug, Clone)]
 pub struct Regex {
     pub steps: Vec<RegexStep>,
+    pub options: RegexOptions,
+    pub groups: usize,
+    source: String,
 }
 
+/// Start and end positions of the whole match and of each group, None if the group did not participate
+///
+type Captures = Vec<Option<(usize, usize)>>;
+
 #[derive(Debug, Clone)]
 pub struct LineEvaluated {
     pub result: bool,
     pub line: String,
+    pub matched_len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegexMatch {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl RegexMatch {
+    /// Given the string where the match was found, returns the matched substring
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string where the match was found
+    ///
+    /// # Returns
+    ///
+    /// * &str - The matched substring
+    ///
+    pub fn as_str<'v>(&self, value: &'v str) -> &'v str {
+        &value[self.start..self.end]
+    }
+}
+
+/// Kind of a step of a compiled regex, as seen from outside of the engine
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepKind {
+    Literal(char),
+    Wildcard,
+    Class,
+    NotClass,
+    Bracket,
+    NotBracket,
+    Group,
+    Alternation,
+    LineStart,
+    LineEnd,
+    WordStart,
+    WordEnd,
+    TextStart,
+    TextEnd,
+}
+
+/// Read-only view of a step of a compiled regex
+///
+#[derive(Debug, Clone, Copy)]
+pub struct StepView<'r> {
+    step: &'r RegexStep,
+}
+
+impl<'r> StepView<'r> {
+    /// Returns the kind of the step
+    ///
+    /// # Returns
+    ///
+    /// * StepKind - The kind of the step, the anchors '^' and '$' are LineStart and LineEnd
+    ///
+    pub fn kind(&self) -> StepKind {
+        if self.step.anchoring_start {
+            return StepKind::LineStart;
+        }
+        if self.step.anchoring_end {
+            return StepKind::LineEnd;
+        }
+
+        match &self.step.val {
+            RegexVal::Literal(c) => StepKind::Literal(*c),
+            RegexVal::Wildcard => StepKind::Wildcard,
+            RegexVal::Class(_) => StepKind::Class,
+            RegexVal::NotClass(_) => StepKind::NotClass,
+            RegexVal::Bracket(_) => StepKind::Bracket,
+            RegexVal::NotBracket(_) => StepKind::NotBracket,
+            RegexVal::Group { .. } => StepKind::Group,
+            RegexVal::Alternation(_) => StepKind::Alternation,
+            RegexVal::WordStart => StepKind::WordStart,
+            RegexVal::WordEnd => StepKind::WordEnd,
+            RegexVal::TextStart => StepKind::TextStart,
+            RegexVal::TextEnd => StepKind::TextEnd,
+        }
+    }
+
+    /// Returns the repetition of the step
+    ///
+    /// # Returns
+    ///
+    /// * &RegexRep - How many times the step can be repeated
+    ///
+    pub fn rep(&self) -> &'r RegexRep {
+        &self.step.rep
+    }
 }
 
 /// Point character for a regex
@@ -48,22 +154,45 @@ fn point_char() -> Option<RegexStep> {
     })
 }
 
+/// Given the steps of a regex, returns the last step if a repetition can be applied to it.
+/// A repetition can not be applied to an anchoring step.
+///
+fn last_quantifiable(steps: &mut [RegexStep]) -> Option<&mut RegexStep> {
+    match steps.last_mut() {
+        Some(last) if !last.anchoring_start && !last.anchoring_end => Some(last),
+        _ => None,
+    }
+}
+
+/// Given the steps before a quantifier and the options of the regex, returns the literal step
+/// of the quantifier if there is no preceding element and leading quantifiers are literals, as in grep
+///
+fn leading_literal(
+    steps: &mut [RegexStep],
+    quantifier: char,
+    options: &RegexOptions,
+) -> Option<RegexStep> {
+    if options.literal_leading_quantifiers && last_quantifiable(steps).is_none() {
+        regular_char(quantifier)
+    } else {
+        None
+    }
+}
+
 /// Wildcard character for a regex
 /// "*" - Matches zero or more of the preceding element
 ///
 fn wildcard_char(steps: &mut [RegexStep]) -> Option<RegexStep> {
-    if steps.is_empty() {
+    if let Some(last) = last_quantifiable(steps) {
+        last.rep = RegexRep::Any;
+        None
+    } else {
         Some(RegexStep {
             rep: RegexRep::Any,
             val: RegexVal::Wildcard,
             anchoring_start: false,
             anchoring_end: false,
         })
-    } else {
-        if let Some(last) = steps.last_mut() {
-            last.rep = RegexRep::Any;
-        }
-        None
     }
 }
 
@@ -71,7 +200,13 @@ fn wildcard_char(steps: &mut [RegexStep]) -> Option<RegexStep> {
 /// "?" - Matches zero or one of the preceding element
 ///
 fn option_char(steps: &mut [RegexStep]) -> Option<RegexStep> {
-    if steps.is_empty() {
+    if let Some(last) = last_quantifiable(steps) {
+        last.rep = RegexRep::Range {
+            min: Some(0),
+            max: Some(1),
+        };
+        None
+    } else {
         Some(RegexStep {
             rep: RegexRep::Range {
                 min: Some(0),
@@ -81,14 +216,6 @@ fn option_char(steps: &mut [RegexStep]) -> Option<RegexStep> {
             anchoring_start: false,
             anchoring_end: false,
         })
-    } else {
-        if let Some(last) = steps.last_mut() {
-            last.rep = RegexRep::Range {
-                min: Some(0),
-                max: Some(1),
-            };
-        }
-        None
     }
 }
 
@@ -96,7 +223,13 @@ fn option_char(steps: &mut [RegexStep]) -> Option<RegexStep> {
 /// "+" - Matches one or more of the preceding element
 ///
 fn option_one_or_more_char(steps: &mut [RegexStep]) -> Option<RegexStep> {
-    if steps.is_empty() {
+    if let Some(last) = last_quantifiable(steps) {
+        last.rep = RegexRep::Range {
+            min: Some(1),
+            max: None,
+        };
+        None
+    } else {
         Some(RegexStep {
             rep: RegexRep::Range {
                 min: Some(1),
@@ -106,97 +239,123 @@ fn option_one_or_more_char(steps: &mut [RegexStep]) -> Option<RegexStep> {
             anchoring_start: false,
             anchoring_end: false,
         })
-    } else {
-        if let Some(last) = steps.last_mut() {
-            last.rep = RegexRep::Range {
-                min: Some(1),
-                max: None,
-            };
-        }
-        None
     }
 }
 
 /// Repetition character for a regex
 /// "{" - Matches the preceding element a specified number of times
 /// "}" - End of the specified number of times
+/// A bound without digits is not limited, while "{0,0}" or "{,0}" only match an empty repetition.
+/// A minimum greater than the maximum, or a bound that does not fit in a usize, is an invalid range.
+/// As with the other quantifiers, when there is no preceding element it is applied to a point.
 ///
 fn repetition_char(
     steps: &mut [RegexStep],
     chars_iter: &mut Chars<'_>,
 ) -> Result<Option<RegexStep>, &'static str> {
-    if let Some(last) = steps.last_mut() {
-        let mut min = None;
-        let mut max = None;
-        let mut count = 0;
-        let mut is_comma = false;
-        let mut is_end = false;
-        let mut is_invalid = false;
-
-        for c in chars_iter.by_ref() {
-            match c {
-                '0'..='9' => {
-                    count = count * 10 + c.to_digit(10).unwrap() as usize;
-                }
-                ',' => {
-                    if is_comma {
-                        is_invalid = true;
-                        break;
-                    }
-                    is_comma = true;
+    let mut min = None;
+    let mut count: Option<usize> = None;
+    let mut is_comma = false;
+    let mut is_end = false;
+    let mut is_invalid = false;
 
-                    if count > 0 {
-                        min = Some(count);
-                        count = 0;
-                    }
-                }
-                '}' => {
-                    is_end = true;
-                    break;
+    for c in chars_iter.by_ref() {
+        match c {
+            '0'..='9' => {
+                let digit = c.to_digit(10).unwrap() as usize;
+                let number = count.unwrap_or(0);
+                match number.checked_mul(10).and_then(|n| n.checked_add(digit)) {
+                    Some(number) => count = Some(number),
+                    None => return Err(RegexError::InvalidRange.message()),
                 }
-                _ => {
+            }
+            ',' => {
+                if is_comma {
                     is_invalid = true;
                     break;
                 }
+                is_comma = true;
+                min = count.take();
+            }
+            '}' => {
+                is_end = true;
+                break;
+            }
+            _ => {
+                is_invalid = true;
+                break;
             }
         }
+    }
 
-        if is_invalid || !is_end {
-            return Err(RegexError::InvalidRange.message());
-        }
+    if is_invalid || !is_end {
+        return Err(RegexError::InvalidRange.message());
+    }
 
-        if count > 0 {
-            max = Some(count);
+    let rep = if !is_comma {
+        RegexRep::Exact(count.unwrap_or(0))
+    } else {
+        let max = count;
+        if min.zip(max).is_some_and(|(min, max)| min > max) {
+            return Err(RegexError::InvalidRange.message());
         }
+        RegexRep::Range { min, max }
+    };
 
-        if !is_comma {
-            last.rep = RegexRep::Exact(count);
-        } else {
-            last.rep = RegexRep::Range { min, max };
-        }
+    if let Some(last) = last_quantifiable(steps) {
+        last.rep = rep;
+        Ok(None)
+    } else {
+        Ok(Some(RegexStep {
+            rep,
+            val: RegexVal::Wildcard,
+            anchoring_start: false,
+            anchoring_end: false,
+        }))
     }
-    Ok(None)
 }
 
-/// Anchor character for a regex
-/// "^" - Anchors the regex at the start of the line
+/// Returns if the character is a repetition operator: "*", "?", "+" or "{"
 ///
-fn anchor_start_char(anchoring_start: &mut bool) -> Option<RegexStep> {
-    *anchoring_start = true;
-    None
+fn is_repetition_char(c: char) -> bool {
+    matches!(c, '*' | '?' | '+' | '{')
 }
 
 /// Anchor character for a regex
-/// "$" - Anchors the regex at the end of the line
+/// "^" - Anchors the regex at the start of the line.
+/// It is only an anchor when it is the first token of the regex, of a group or of an alternative,
+/// otherwise it is a literal. A repeated anchor is the same as a single one.
 ///
-fn anchor_end_char(steps: &mut Vec<RegexStep>) -> Option<RegexStep> {
-    let end_regex = RegexStep {
+fn anchor_start_char(steps: &[RegexStep]) -> Option<RegexStep> {
+    if !steps.iter().all(|step| step.anchoring_start) {
+        return regular_char('^');
+    }
+    if !steps.is_empty() {
+        return None;
+    }
+
+    Some(RegexStep {
         rep: RegexRep::Any,
         val: RegexVal::Wildcard,
-        anchoring_start: false,
+        anchoring_start: true,
         anchoring_end: false,
-    };
-    steps.insert(0, end_regex);
+    })
+}
+
+/// Anchor character for a regex
+/// "$" - Anchors the regex at the end of the line.
+/// It is only an anchor when it is the last token of the regex, of a group or of an alternative,
+/// otherwise it is a literal. A repeated anchor is the same as a single one.
+///
+fn anchor_end_char(chars_iter: &Chars<'_>) -> Option<RegexStep> {
+    let rest = chars_iter.as_str().trim_start_matches('$');
+    if !(rest.is_empty() || rest.starts_with(')') || rest.starts_with('|')) {
+        return regular_char('$');
+    }
+    if chars_iter.as_str().starts_with('$') {
+        return None;
+    }
+
     Some(RegexStep {
         rep: RegexRep::Any,
         val: RegexVal::Wildcard,
@@ -207,52 +366,29 @@ fn anchor_end_char(steps: &mut Vec<RegexStep>) -> Option<RegexStep> {
 
 /// Bracket character for a regex
 /// "[" - Matches any character in the brackets
-/// "]" - End of the bracket
+/// "]" - End of the bracket, a ']' right after "[" or "[^" is part of the bracket
+/// "-" - Range of characters between the previous and the next character, a '-' at the start
+/// or at the end of the bracket, or escaped as "\\-", is a literal
+/// "[:name:]" - Character class inside the bracket, an unterminated class or an unknown name is invalid,
+/// while a '[' that is not followed by ':' is a literal. Classes can be negated with "^"
+/// and mixed with other members, as in "[^[:digit:]x]"
+/// "\\" - Escapes the following character, which is always taken as a literal,
+/// so "[\\b]" matches a 'b' and not a backspace
 ///
 fn bracket_char(chars_iter: &mut Chars<'_>) -> Result<Option<RegexStep>, &'static str> {
     let mut negated = false;
-    let mut vec = Vec::new();
-    let mut is_regex_class = false;
+    let mut members = Vec::new();
+    let mut classes = Vec::new();
 
-    if let Some(c) = chars_iter.next() {
-        if c == '^' {
-            negated = true;
-        } else if c == '[' {
-            is_regex_class = true;
-        } else {
-            vec.push(c);
-        }
-    } else {
-        return Err(RegexError::InvalidBracket.message());
+    if chars_iter.as_str().starts_with('^') {
+        chars_iter.next();
+        negated = true;
     }
-
-    let mut end_bracket = false;
-    let mut regex_class = None;
-    if is_regex_class && chars_iter.next() == Some(':') {
-        let mut class_vec = Vec::new();
-        let mut end_class = false;
-        while let Some(c) = chars_iter.next() {
-            if c == ':' && chars_iter.next() == Some(']') {
-                end_class = true;
-                break;
-            }
-            class_vec.push(c);
-        }
-
-        if !end_class {
-            return Err(RegexError::InvalidClass.message());
-        }
-
-        let class: String = class_vec.iter().collect();
-        let character_class = determinate_regex_class(class);
-        match character_class {
-            Ok(class) => {
-                regex_class = Some(class);
-            }
-            Err(_) => return Err(RegexError::InvalidClass.message()),
-        }
+    if chars_iter.as_str().starts_with(']') {
+        members.extend(chars_iter.next().map(|c| (c, negated)));
     }
 
+    let mut end_bracket = false;
     while let Some(c) = chars_iter.next() {
         match c {
             ']' => {
@@ -261,12 +397,16 @@ fn bracket_char(chars_iter: &mut Chars<'_>) -> Result<Option<RegexStep>, &'stati
             }
             '\\' => {
                 if let Some(literal) = chars_iter.next() {
-                    vec.push(literal);
+                    members.push((literal, true));
                 } else {
                     return Err(RegexError::InvalidBackslash.message());
                 }
             }
-            _ => vec.push(c),
+            '[' if chars_iter.as_str().starts_with(':') => {
+                chars_iter.next();
+                classes.push(bracket_class(chars_iter)?);
+            }
+            _ => members.push((c, false)),
         }
     }
 
@@ -274,14 +414,24 @@ fn bracket_char(chars_iter: &mut Chars<'_>) -> Result<Option<RegexStep>, &'stati
         return Err(RegexError::InvalidBracket.message());
     }
 
-    let val;
-    if let Some(class) = regex_class {
-        val = RegexVal::Class(class);
-    } else if negated {
-        val = RegexVal::NotBracket(vec);
-    } else {
-        val = RegexVal::Bracket(vec);
-    }
+    let vec = expand_ranges(&members)?;
+
+    // A bracket with only a class is matched as the class itself
+    let val = match classes.as_slice() {
+        [class] if vec.is_empty() && !negated => RegexVal::Class(class.clone()),
+        [class] if vec.is_empty() => RegexVal::NotClass(class.clone()),
+        _ => {
+            let mut set = CharSet::from(vec);
+            for class in classes {
+                set.insert_class(class);
+            }
+            if negated {
+                RegexVal::NotBracket(set)
+            } else {
+                RegexVal::Bracket(set)
+            }
+        }
+    };
 
     Ok(Some(RegexStep {
         rep: RegexRep::Exact(1),
@@ -291,19 +441,114 @@ fn bracket_char(chars_iter: &mut Chars<'_>) -> Result<Option<RegexStep>, &'stati
     }))
 }
 
+/// Given the chars that follow the "[:" of a class inside a bracket, returns the class,
+/// consuming the chars until its ":]".
+/// An unterminated class, an empty name or an unknown name is invalid.
+///
+fn bracket_class(chars_iter: &mut Chars<'_>) -> Result<RegexClass, &'static str> {
+    let mut class_vec = Vec::new();
+    let mut end_class = false;
+    while let Some(c) = chars_iter.next() {
+        if c == ':' && chars_iter.as_str().starts_with(']') {
+            chars_iter.next();
+            end_class = true;
+            break;
+        }
+        class_vec.push(c);
+    }
+
+    if !end_class {
+        return Err(RegexError::InvalidClass.message());
+    }
+
+    let class: String = class_vec.iter().collect();
+    // An empty class name, or one with only spaces, is not looked up as a class
+    if class.trim().is_empty() {
+        return Err(RegexError::InvalidClass.message());
+    }
+    determinate_regex_class(class).map_err(|_| RegexError::InvalidClass.message())
+}
+
+/// Given the members of a bracket and if each one was escaped, returns the chars of the bracket.
+/// An unescaped '-' between two members is replaced by every char in the range between them.
+///
+fn expand_ranges(members: &[(char, bool)]) -> Result<Vec<char>, &'static str> {
+    let mut vec = Vec::new();
+    let mut i = 0;
+    while i < members.len() {
+        let (start, _) = members[i];
+        if i + 2 < members.len() && members[i + 1] == ('-', false) {
+            let (end, _) = members[i + 2];
+            if start > end {
+                return Err(RegexError::InvalidRange.message());
+            }
+            vec.extend(start..=end);
+            i += 3;
+        } else {
+            vec.push(start);
+            i += 1;
+        }
+    }
+    Ok(vec)
+}
+
 /// Escape character for a regex
-/// "\\" - Escapes the following character
+/// "\\" - Escapes the following character, so "\\|" is a literal '|' and not an alternation
+/// "\\w" - Matches a word character, a letter, a digit or '_', and "\\W" any other character
+/// "\\<" - Matches the start of a word, and "\\>" the end of a word, without consuming characters
+/// "\\A" - Matches the start of the whole text, and "\\z" its end, even in multiline mode
 ///
 fn escape_char(chars_iter: &mut Chars<'_>) -> Result<Option<RegexStep>, &'static str> {
-    match chars_iter.next() {
-        Some(literal) => Ok(Some(RegexStep {
-            rep: RegexRep::Exact(1),
-            val: RegexVal::Literal(literal),
-            anchoring_start: false,
-            anchoring_end: false,
-        })),
+    let val = match chars_iter.next() {
+        Some('w') => RegexVal::Class(RegexClass::Word),
+        Some('W') => RegexVal::NotClass(RegexClass::Word),
+        Some('<') => RegexVal::WordStart,
+        Some('>') => RegexVal::WordEnd,
+        Some('A') => RegexVal::TextStart,
+        Some('z') => RegexVal::TextEnd,
+        Some(literal) => RegexVal::Literal(literal),
         None => return Err(RegexError::InvalidBackslash.message()),
+    };
+
+    Ok(Some(RegexStep {
+        rep: RegexRep::Exact(1),
+        val,
+        anchoring_start: false,
+        anchoring_end: false,
+    }))
+}
+
+/// Group for a regex
+/// "(" ... ")" - Matches the steps inside the group, and captures the matched substring
+///
+fn group_char(index: usize, steps: Vec<RegexStep>) -> Option<RegexStep> {
+    Some(RegexStep {
+        rep: RegexRep::Exact(1),
+        val: RegexVal::Group { index, steps },
+        anchoring_start: false,
+        anchoring_end: false,
+    })
+}
+
+/// Alternation for a regex
+/// "|" - Matches the steps before it or the steps after it, trying the alternatives from left to right.
+/// Given the alternatives already finished and the steps of the last one, returns the steps of the whole regex or group.
+///
+fn alternation_steps(
+    mut alternatives: Vec<Vec<RegexStep>>,
+    steps: Vec<RegexStep>,
+) -> Vec<RegexStep> {
+    if alternatives.is_empty() {
+        return steps;
     }
+    alternatives.push(steps);
+
+    vec![RegexStep {
+        rep: RegexRep::Exact(1),
+        val: RegexVal::Alternation(alternatives),
+        anchoring_start: false,
+        anchoring_end: false,
+    }]
 }
 
 /// Regular character for a regex
@@ -330,12 +575,22 @@ impl TryFrom<&str> for Regex {
     /// * '?' - Matches zero or one of the preceding element
     /// * '+' - Matches one or more of the preceding element
     /// * '{' - Matches the preceding element a specified number of times
-    /// * '}' - End of the specified number of times
+    /// * '}' - End of the specified number of times, a '}' without a previous '{' is a literal
     /// * '[' - Matches any character in the brackets
     /// * ']' - End of the bracket
-    /// * '^' - Anchors the regex at the start of the line
-    /// * '$' - Anchors the regex at the end of the line
-    /// * '\\' - Escapes the following character
+    /// * '^' - Anchors the regex at the start of the line, a '^' that is not the first token is a literal
+    /// * '$' - Anchors the regex at the end of the line, a '$' that is not the last token is a literal
+    /// * '(' - Starts a group, its match is captured
+    /// * ')' - End of the group
+    /// * '|' - Separates the alternatives of the regex or of a group
+    /// * '\\' - Escapes the following character, "\\w" and "\\W" match a word and a non word character
+    ///
+    /// A repetition operator can not follow another repetition operator, for example "a**" or "a+{2}".
+    /// A quantifier without a preceding element is applied to a point, see Regex::with_options
+    /// to treat it as a literal instead.
+    /// A '{' that does not form a valid repetition is an invalid range, use "\\{" to match a literal '{'.
+    /// The empty alternatives of the whole regex are ignored, and a regex without any step,
+    /// like "" or "|", is an empty pattern error instead of matching every string as in grep.
     ///
     /// # Arguments
     ///
@@ -347,217 +602,594 @@ impl TryFrom<&str> for Regex {
     /// * Error - The corresponding error if the string is not a valid regex
     ///
     fn try_from(expression: &str) -> Result<Self, Self::Error> {
-        let mut steps: Vec<RegexStep> = vec![];
-        let mut anchoring_start = false;
+        Regex::with_options(expression, &RegexOptions::default())
+    }
+}
 
-        let mut chars_iter = expression.chars();
-        while let Some(c) = chars_iter.next() {
-            let step = match c {
-                '.' => point_char(),
-                '*' => wildcard_char(&mut steps),
-                '?' => option_char(&mut steps),
-                '+' => option_one_or_more_char(&mut steps),
-                '{' => repetition_char(&mut steps, &mut chars_iter)?,
-                '^' => anchor_start_char(&mut anchoring_start),
-                '$' => anchor_end_char(&mut steps),
-                '[' => bracket_char(&mut chars_iter)?,
-                '\\' => escape_char(&mut chars_iter)?,
-                _ => regular_char(c),
-            };
+/// Matches the steps of a regex against a string with the options of the regex.
+/// A lazy matcher tries the fewest repetitions first instead of the most.
+/// Every match attempt is counted, and once the step limit of the options is exceeded all the attempts fail.
+/// The failures of the steps are remembered for each context where the steps are matched in the same way,
+/// so a slice of steps is never tried twice from the same position of a context.
+/// The search of a memoizing matcher is a context, and so is each repetition of a group.
+///
+struct Matcher<'a> {
+    value: &'a str,
+    options: &'a RegexOptions,
+    lazy: bool,
+    attempts: Cell<usize>,
+    context: Cell<Option<usize>>,
+    contexts: Cell<usize>,
+    failed: RefCell<HashSet<MemoKey>>,
+}
 
-            if let Some(s) = step {
-                steps.push(s);
-            }
+/// The context, the start and the length of a slice of steps, and the position where it failed
+///
+type MemoKey = (usize, usize, usize, usize);
+
+impl<'a> Matcher<'a> {
+    fn new(value: &'a str, options: &'a RegexOptions, lazy: bool) -> Self {
+        Matcher {
+            value,
+            options,
+            lazy,
+            attempts: Cell::new(0),
+            context: Cell::new(None),
+            contexts: Cell::new(0),
+            failed: RefCell::new(HashSet::new()),
         }
+    }
 
-        if anchoring_start {
-            let start_regex = RegexStep {
-                rep: RegexRep::Any,
-                val: RegexVal::Wildcard,
-                anchoring_start: true,
-                anchoring_end: false,
-            };
-            steps.push(start_regex);
-        }
+    /// Returns the matcher remembering the failures of the whole regex.
+    /// It is only valid while every match of the whole regex ends the search in the same way,
+    /// since a failure of some steps can not depend on what is done after the regex.
+    ///
+    fn memoizing(self) -> Self {
+        self.context.set(Some(0));
+        self
+    }
 
-        Ok(Regex { steps })
+    /// Returns a context that was not used before, for the repetitions of a group
+    ///
+    fn new_context(&self) -> usize {
+        let context = self.contexts.get() + 1;
+        self.contexts.set(context);
+        context
     }
-}
 
-/// Given a queue of RegexSteps, a string and a state, returns a LineEvaluated if the string matches the regex
-/// The function iterates over the string and the queue of RegexSteps to evaluate the match
-/// The function returns a LineEvaluated with the result of the evaluation
-/// The function is recursive and uses a stack to backtrack when needed
-/// The function is used by the evaluate method of the Regex struct
-///
-fn evaluate_step(
-    queue: &mut VecDeque<RegexStep>,
-    value: &str,
-    mut state: bool,
-    queue_size: usize,
-) -> Result<LineEvaluated, &'static str> {
-    let regex_len = queue.len();
-    for char_index in 0..value.len() {
-        let mut stack: Vec<EvaluatedStep> = Vec::new();
-        let mut index = char_index;
-
-        'steps: while let Some(step) = queue.pop_front() {
-            if step.anchoring_start {
-                if index == regex_len - 1 {
-                    return Ok(LineEvaluated {
-                        result: true,
-                        line: value.to_string(),
-                    });
-                } else {
-                    break 'steps;
-                }
-            }
+    /// Given a slice of RegexSteps and a position, returns the key of its failures
+    /// if the failures of the current context are remembered.
+    /// An empty slice only calls what follows it, so it is not remembered.
+    ///
+    fn memo_key(&self, steps: &[RegexStep], index: usize) -> Option<MemoKey> {
+        let context = self.context.get()?;
+        if steps.is_empty() {
+            return None;
+        }
+        Some((context, steps.as_ptr() as usize, steps.len(), index))
+    }
 
-            if step.anchoring_end {
-                if index == value.len() {
-                    return Ok(LineEvaluated {
-                        result: true,
-                        line: value.to_string(),
-                    });
-                } else {
-                    break 'steps;
-                }
-            }
+    /// Given a slice of RegexSteps and a position, returns if the slice already failed from the position
+    ///
+    fn has_failed(&self, steps: &[RegexStep], index: usize) -> bool {
+        self.memo_key(steps, index)
+            .is_some_and(|key| self.failed.borrow().contains(&key))
+    }
 
-            match step.rep {
-                RegexRep::Exact(n) => {
-                    let mut match_size = 0;
-                    for i in 0..n {
-                        let size = step.val.matches(&value[index..]);
-
-                        if size == 0 {
-                            match backtrack(step, &mut stack, queue) {
-                                Some(size) => {
-                                    index -= size;
-                                    continue 'steps;
-                                }
-                                None => {
-                                    break 'steps;
-                                }
-                            }
-                        } else {
-                            if queue.is_empty() && i == n - 1 {
-                                state = true;
-                                break 'steps;
-                            }
-                            match_size += size;
-                            index += size;
-                        }
-                    }
-                    stack.push(EvaluatedStep {
-                        step,
-                        match_size,
-                        backtrackable: false,
-                    })
-                }
-                RegexRep::Any => {
-                    let mut is_match = false;
-                    let mut keep_matching = true;
-                    while keep_matching {
-                        let match_size = step.val.matches(&value[index..]);
-
-                        if match_size != 0 {
-                            is_match = true;
-                            index += match_size;
-                            stack.push(EvaluatedStep {
-                                step: step.clone(),
-                                match_size,
-                                backtrackable: true,
-                            });
-                        } else {
-                            keep_matching = false;
-                        }
-                    }
+    /// Given a slice of RegexSteps and a position, remembers that the slice fails from the position,
+    /// and returns if it was not known before
+    ///
+    fn set_failed(&self, steps: &[RegexStep], index: usize) -> bool {
+        match self.memo_key(steps, index) {
+            Some(key) => self.failed.borrow_mut().insert(key),
+            None => false,
+        }
+    }
 
-                    if queue.is_empty() {
-                        state = true;
-                        break 'steps;
-                    }
-                    if !is_match {
-                        continue 'steps;
-                    }
-                }
-                RegexRep::Range { min, max } => {
-                    let mut match_size = 0;
-                    let mut count = 0;
-                    let mut keep_matching = true;
-                    while keep_matching {
-                        let size = step.val.matches(&value[index..]);
-
-                        if size == 0 {
-                            if let Some(min) = min {
-                                if count < min {
-                                    match backtrack(step, &mut stack, queue) {
-                                        Some(size) => {
-                                            index -= size;
-                                            continue 'steps;
-                                        }
-                                        None => {
-                                            break 'steps;
-                                        }
-                                    }
-                                } else if min == 0 {
-                                    state = true;
-                                }
-                            }
-                            keep_matching = false;
-                        } else {
-                            match_size += size;
-                            index += size;
-                            count += 1;
-                        }
-
-                        if let Some(max) = max {
-                            if count >= max {
-                                keep_matching = false;
-                            }
-                        }
-                    }
-                    if queue.is_empty() {
-                        state = true;
-                        break 'steps;
-                    }
+    /// Given a boundary and a position, returns if the position is the start or the end of a word,
+    /// or the start or the end of the whole text.
+    /// A word starts where a word character is not preceded by another one,
+    /// and ends where a word character is not followed by another one.
+    ///
+    fn is_boundary(&self, boundary: &RegexVal, index: usize) -> bool {
+        let is_word = |c: Option<char>| c.is_some_and(|c| RegexClass::Word.matches(c));
+        let before = is_word(self.value[..index].chars().next_back());
+        let after = is_word(self.value[index..].chars().next());
+
+        match boundary {
+            RegexVal::TextStart => index == 0,
+            RegexVal::TextEnd => index == self.value.len(),
+            RegexVal::WordStart => !before && after,
+            _ => before && !after,
+        }
+    }
 
-                    let mut backtrack_status = false;
-                    if let Some(0) = min {
-                        backtrack_status = true;
-                    } else if let Some(max) = max {
-                        if count < max {
-                            backtrack_status = true;
-                        }
-                    }
+    /// Counts a new match attempt, and returns if the step limit was exceeded
+    ///
+    fn limit_exceeded(&self) -> bool {
+        let attempts = self.attempts.get() + 1;
+        self.attempts.set(attempts);
+        self.options
+            .step_limit
+            .is_some_and(|limit| attempts > limit)
+    }
 
-                    stack.push(EvaluatedStep {
-                        step,
-                        match_size,
-                        backtrackable: backtrack_status,
-                    });
-                }
-            }
+    /// Returns the error of the search if the step limit was exceeded
+    ///
+    fn check_limit(&self) -> Result<(), &'static str> {
+        if self
+            .options
+            .step_limit
+            .is_some_and(|limit| self.attempts.get() > limit)
+        {
+            return Err(RegexError::ComplexityLimitExceeded.message());
         }
+        Ok(())
+    }
 
-        if !queue.is_empty() {
-            queue.rotate_left(queue_size - queue.len());
-        } else {
-            break;
+    /// Given a slice of RegexSteps, the current position and the captures,
+    /// returns the position where the match ends if the steps match the string from the current position.
+    /// Each step is repeated as many times as possible, and the function backtracks to fewer repetitions
+    /// when the following steps can not match.
+    /// Anchoring steps do not consume characters, they only check that the current position is the start or end of a line.
+    /// When every step matched, `next` is called with the current position to match what follows the steps.
+    ///
+    fn match_steps(
+        &self,
+        steps: &[RegexStep],
+        index: usize,
+        captures: &mut Captures,
+        next: &mut dyn FnMut(usize, &mut Captures) -> Option<usize>,
+    ) -> Option<usize> {
+        if self.has_failed(steps, index) {
+            return None;
         }
-    }
 
-    Ok(LineEvaluated {
-        result: state,
-        line: value.to_string(),
-    })
-}
+        let found = self.try_steps(steps, index, captures, next);
+        if found.is_none() {
+            self.set_failed(steps, index);
+        }
+        found
+    }
 
-impl Regex {
-    /// Given a string, returns a new Regex if the string is a valid regex
+    /// Matches the steps like `match_steps`, without looking at the failures already remembered.
     ///
-    /// # Arguments
+    fn try_steps(
+        &self,
+        steps: &[RegexStep],
+        index: usize,
+        captures: &mut Captures,
+        next: &mut dyn FnMut(usize, &mut Captures) -> Option<usize>,
+    ) -> Option<usize> {
+        if self.limit_exceeded() {
+            return None;
+        }
+
+        let (step, next_steps) = match steps.split_first() {
+            Some(split) => split,
+            None => return next(index, captures),
+        };
+
+        if step.anchoring_start {
+            if !is_line_start(self.value, index, self.options) {
+                return None;
+            }
+            return self.match_steps(next_steps, index, captures, next);
+        }
+
+        if step.anchoring_end {
+            if !is_line_end(self.value, index, self.options) {
+                return None;
+            }
+            return self.match_steps(next_steps, index, captures, next);
+        }
+
+        if let RegexVal::WordStart | RegexVal::WordEnd | RegexVal::TextStart | RegexVal::TextEnd =
+            step.val
+        {
+            // A boundary does not consume characters, so repeating it does not change the match
+            if !self.is_boundary(&step.val, index) && step.rep.bounds().0 > 0 {
+                return None;
+            }
+            return self.match_steps(next_steps, index, captures, next);
+        }
+
+        if let RegexVal::Group { .. } = step.val {
+            return self.match_group(steps, index, captures, next);
+        }
+
+        if let RegexVal::Alternation(alternatives) = &step.val {
+            return alternatives.iter().find_map(|alternative| {
+                self.match_steps(alternative, index, captures, &mut |end, captures| {
+                    self.match_steps(next_steps, end, captures, next)
+                })
+            });
+        }
+
+        let (min, max) = step.rep.bounds();
+        let mut ends = vec![index];
+        self.repeat_step(step, &mut ends, min.max(1).min(max));
+        if ends.len() <= min {
+            return None;
+        }
+
+        // Without a maximum, the steps from the end of one repetition can only reach the ends
+        // of more repetitions, so once they failed from there only the minimum is left to try
+        let unbounded = max == usize::MAX;
+        let count = if unbounded && ends.len() > 1 && self.has_failed(steps, ends[1]) {
+            min
+        } else {
+            self.repeat_step(step, &mut ends, max);
+            ends.len() - 1
+        };
+
+        let found = if self.lazy {
+            (min..=count).find_map(|repetitions| {
+                self.match_steps(next_steps, ends[repetitions], captures, next)
+            })
+        } else {
+            (min..=count).rev().find_map(|repetitions| {
+                self.match_steps(next_steps, ends[repetitions], captures, next)
+            })
+        };
+
+        if found.is_none() {
+            self.set_failed_run(steps, index);
+        }
+        found
+    }
+
+    /// Given a slice of RegexSteps that failed from a position, remembers that it also fails
+    /// after each repetition of its leading step, when the leading step is repeated without a maximum,
+    /// or is the first step of a group repeated at least once.
+    /// From those positions the steps can only match a part of what they could match from the position.
+    /// Every failure of the slice is remembered in the same way, so once a position was already known
+    /// the following ones are too.
+    ///
+    fn set_failed_run(&self, steps: &[RegexStep], index: usize) {
+        let mut leading = steps;
+        let step = loop {
+            let step = match leading.first() {
+                Some(step) if !step.anchoring_start && !step.anchoring_end => step,
+                _ => return,
+            };
+            let (min, max) = step.rep.bounds();
+            match &step.val {
+                RegexVal::Group { steps, .. } if min > 0 => leading = steps,
+                RegexVal::Group { .. }
+                | RegexVal::Alternation(_)
+                | RegexVal::WordStart
+                | RegexVal::WordEnd
+                | RegexVal::TextStart
+                | RegexVal::TextEnd => return,
+                _ if max == usize::MAX => break step,
+                _ => return,
+            }
+        };
+
+        let mut current = index;
+        while let Some(size) = step.val.matches_with(&self.value[current..], self.options) {
+            current += size;
+            if !self.set_failed(steps, current) {
+                break;
+            }
+        }
+    }
+
+    /// Given a simple step, the ends of its repetitions found so far and a maximum of repetitions,
+    /// adds the end of each following repetition until the step does not match or the maximum is reached
+    ///
+    fn repeat_step(&self, step: &RegexStep, ends: &mut Vec<usize>, max: usize) {
+        while ends.len() <= max {
+            let current = ends[ends.len() - 1];
+            match step.val.matches_with(&self.value[current..], self.options) {
+                Some(size) => ends.push(current + size),
+                None => break,
+            }
+        }
+    }
+
+    /// Given a slice of RegexSteps that starts with a group, the current position and the captures,
+    /// returns the position where the match ends.
+    /// The positions reached after each amount of repetitions are collected level by level,
+    /// so repeating the group does not nest calls and a long repetition can not exhaust the stack.
+    /// Each position keeps the captures of the path that reached it, with the last repetition of the group.
+    /// A repetition that matches an empty string is only accepted while the minimum is not reached,
+    /// and a position already reached with at least the minimum repetitions is not repeated again.
+    /// The following steps are tried from the most repetitions to the fewest, or the other way when lazy.
+    /// Each level is its own context, since every end of the repetitions is collected in the same way.
+    ///
+    fn match_group(
+        &self,
+        steps: &[RegexStep],
+        index: usize,
+        captures: &mut Captures,
+        next: &mut dyn FnMut(usize, &mut Captures) -> Option<usize>,
+    ) -> Option<usize> {
+        let (step, next_steps) = steps.split_first()?;
+        let (group, inner_steps) = match &step.val {
+            RegexVal::Group { index, steps } => (*index, steps),
+            _ => return None,
+        };
+        let (min, max) = step.rep.bounds();
+
+        let mut levels: Vec<Vec<(usize, Captures)>> = vec![vec![(index, captures.clone())]];
+        let mut reached = HashSet::new();
+        if min == 0 {
+            reached.insert(index);
+        }
+
+        while levels.len() - 1 < max {
+            let count = levels.len() - 1;
+            let mut level = Vec::new();
+            let mut in_level = HashSet::new();
+            let parent = self.context.replace(Some(self.new_context()));
+
+            for (start, start_captures) in &levels[count] {
+                let mut inner = start_captures.clone();
+                self.match_steps(inner_steps, *start, &mut inner, &mut |end, inner| {
+                    if end == *start && count >= min {
+                        return None;
+                    }
+                    let enough = count + 1 >= min;
+                    if (enough && reached.contains(&end)) || !in_level.insert(end) {
+                        return None;
+                    }
+                    if enough {
+                        reached.insert(end);
+                    }
+
+                    let mut found = inner.clone();
+                    found[group] = Some((*start, end));
+                    level.push((end, found));
+                    // Every end of the repetition is collected, so the search continues
+                    None
+                });
+            }
+            self.context.set(parent);
+
+            if level.is_empty() {
+                break;
+            }
+            levels.push(level);
+        }
+
+        let original = levels[0][0].1.clone();
+        let counts = min..levels.len();
+        let counts: Box<dyn Iterator<Item = usize>> = if self.lazy {
+            Box::new(counts)
+        } else {
+            Box::new(counts.rev())
+        };
+
+        for count in counts {
+            for (position, state) in &levels[count] {
+                captures.clone_from(state);
+                if let Some(found) = self.match_steps(next_steps, *position, captures, next) {
+                    return Some(found);
+                }
+            }
+        }
+
+        // Without a maximum, the positions reached with more repetitions can only reach
+        // what was already tried from this position
+        if max == usize::MAX {
+            for (position, _) in levels.iter().skip(1).flatten() {
+                self.set_failed(steps, *position);
+            }
+        }
+        self.set_failed_run(steps, index);
+
+        *captures = original;
+        None
+    }
+}
+
+/// Given a slice of RegexSteps, a string and the options of a regex, returns if every literal
+/// that the steps require at least once appears in the string.
+/// The literals of a required group are also required, and an alternation only requires
+/// that the literals of one of its alternatives appear.
+///
+fn has_literals(steps: &[RegexStep], value: &str, options: &RegexOptions) -> bool {
+    steps
+        .iter()
+        .filter(|step| step.rep.bounds().0 > 0)
+        .all(|step| match &step.val {
+            RegexVal::Literal(_) => value
+                .char_indices()
+                .any(|(i, _)| step.val.matches_with(&value[i..], options).is_some()),
+            RegexVal::Group { steps, .. } => has_literals(steps, value, options),
+            RegexVal::Alternation(alternatives) => alternatives
+                .iter()
+                .any(|alternative| has_literals(alternative, value, options)),
+            _ => true,
+        })
+}
+
+/// Given a string, a position and the options of a regex, returns if the position is the start of a line.
+/// In multiline mode the start of a line is also the position after a newline.
+///
+fn is_line_start(value: &str, position: usize, options: &RegexOptions) -> bool {
+    position == 0 || (options.multiline && value[..position].ends_with('\n'))
+}
+
+/// Given a string, a position and the options of a regex, returns if the position is the end of a line.
+/// In multiline mode the end of a line is also the position before a newline.
+///
+fn is_line_end(value: &str, position: usize, options: &RegexOptions) -> bool {
+    position == value.len() || (options.multiline && value[position..].starts_with('\n'))
+}
+
+/// Given the steps of a regex, a function that takes the step at one of the ends and
+/// a function that tells if a step is an anchor, returns if the steps are anchored at that end.
+/// An alternation is anchored if all its alternatives are, and a group if it is matched at least once
+/// and its steps are anchored.
+///
+fn is_anchored(
+    steps: &[RegexStep],
+    end_step: fn(&[RegexStep]) -> Option<&RegexStep>,
+    is_anchor: fn(&RegexStep) -> bool,
+) -> bool {
+    let step = match end_step(steps) {
+        Some(step) => step,
+        None => return false,
+    };
+    if is_anchor(step) {
+        return true;
+    }
+
+    match &step.val {
+        RegexVal::Alternation(alternatives) => alternatives
+            .iter()
+            .all(|alternative| is_anchored(alternative, end_step, is_anchor)),
+        RegexVal::Group { steps, .. } => {
+            step.rep.bounds().0 > 0 && is_anchored(steps, end_step, is_anchor)
+        }
+        _ => false,
+    }
+}
+
+/// Given a string and the positions of a match, returns the position where the next match can start.
+/// After an empty match the next match starts one character later, None if there are no characters left.
+///
+fn position_after(value: &str, start: usize, end: usize) -> Option<usize> {
+    if end > start {
+        return Some(end);
+    }
+    value[end..].chars().next().map(|c| end + c.len_utf8())
+}
+
+/// Given a replacement template, the string where the match was found and the captures of the match,
+/// appends the replacement to the result.
+/// "$n" is replaced by the substring captured by the group n, empty if the group does not exist
+/// or did not participate, and "$$" is replaced by a literal '$'.
+///
+fn expand_replacement(template: &str, value: &str, captures: &Captures, result: &mut String) {
+    let mut chars_iter = template.chars().peekable();
+    while let Some(c) = chars_iter.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        if chars_iter.next_if_eq(&'$').is_some() {
+            result.push('$');
+            continue;
+        }
+
+        let mut digits = String::new();
+        while let Some(digit) = chars_iter.next_if(|d| d.is_ascii_digit()) {
+            digits.push(digit);
+        }
+
+        match digits.parse::<usize>() {
+            Ok(group) => {
+                if let Some(Some((start, end))) = captures.get(group) {
+                    result.push_str(&value[*start..*end]);
+                }
+            }
+            Err(_) => result.push('$'),
+        }
+    }
+}
+
+/// Given a string, returns the pattern that matches it literally,
+/// escaping every metacharacter with a backslash
+///
+/// # Arguments
+///
+/// * `text` - The literal text to be escaped
+///
+/// # Returns
+///
+/// * String - The escaped pattern
+///
+/// # Examples
+///
+/// ```
+/// use rgrep::regex::escape;
+///
+/// assert_eq!(escape("a.b*c|d"), "a\\.b\\*c\\|d");
+/// ```
+///
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if METACHARACTERS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Given a byte, returns the char it is matched as.
+/// Non-ASCII bytes are mapped to private use chars, which are not part of any class or literal of a regex.
+///
+fn byte_char(byte: u8) -> char {
+    if byte.is_ascii() {
+        char::from(byte)
+    } else {
+        char::from_u32(NON_ASCII_BYTES_START + u32::from(byte))
+            .unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+}
+
+/// Iterator over the non-overlapping matches of a Regex in a string
+///
+pub struct Matches<'r, 'v> {
+    regex: &'r Regex,
+    value: &'v str,
+    position: Option<usize>,
+}
+
+impl Iterator for Matches<'_, '_> {
+    type Item = RegexMatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let position = self.position?;
+        let found = self.regex.find_at(self.value, position);
+
+        self.position = match &found {
+            Some(found) => position_after(self.value, found.start, found.end),
+            None => None,
+        };
+
+        found
+    }
+}
+
+/// Iterator over the overlapping matches of a Regex in a string.
+/// After each match, the search continues one character after the start of the match
+///
+pub struct OverlappingMatches<'r, 'v> {
+    regex: &'r Regex,
+    value: &'v str,
+    position: Option<usize>,
+}
+
+impl Iterator for OverlappingMatches<'_, '_> {
+    type Item = RegexMatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let position = self.position?;
+        let found = self.regex.find_at(self.value, position);
+
+        self.position = match &found {
+            Some(found) => position_after(self.value, found.start, found.start),
+            None => None,
+        };
+
+        found
+    }
+}
+
+impl Regex {
+    /// Given a string, returns a new Regex if the string is a valid regex.
+    /// An empty string is not a valid regex, it returns the empty pattern error,
+    /// so a search for nothing is reported instead of silently matching every line or none.
+    ///
+    /// # Arguments
     ///
     /// * `expression` - A string to be checked
     ///
@@ -569,1009 +1201,3258 @@ impl Regex {
     /// # Examples
     ///
     /// ```
+    /// use rgrep::regex::regex_error::RegexError;
     /// use rgrep::regex::Regex;
     ///
     /// let regex = Regex::new("abc.*").unwrap();
+    ///
+    /// assert_eq!(Regex::new("").unwrap_err(), RegexError::EmptyPattern.message());
     /// ```
     ///
     pub fn new(expression: &str) -> Result<Self, &str> {
         Regex::try_from(expression)
     }
 
-    /// Given a string, returns a LineEvaluated if the string matches the regex
+    /// Given a string and the options of a regex, returns a new Regex with the options if the string is a valid regex.
+    /// The characters are converted as in Regex::try_from, but a '*', '+' or '?' without a preceding
+    /// element is a literal when the options treat leading quantifiers as literals.
     ///
     /// # Arguments
     ///
-    /// * `value` - A string to be checked
+    /// * `expression` - A string to be checked
+    /// * `options` - The options of the regex
     ///
     /// # Returns
     ///
-    /// * LineEvaluated - The result of the evaluation
-    /// * &str - The corresponding error if the string contains non-ascii characters
+    /// * Regex - The corresponding Regex if the string is a valid regex
+    /// * &str - The corresponding error if the string is not a valid regex
     ///
     /// # Examples
     ///
     /// ```
+    /// use rgrep::regex::regex_options::RegexOptions;
     /// use rgrep::regex::Regex;
     ///
-    /// let regex = Regex::new("abc.*").unwrap();
-    /// let line = regex.evaluate("abcdefg").unwrap();
+    /// let options = RegexOptions {
+    ///     literal_leading_quantifiers: true,
+    ///     ..Default::default()
+    /// };
+    /// let regex = Regex::with_options("*abc", &options).unwrap();
     ///
-    /// assert_eq!(line.result, true);
+    /// assert!(regex.evaluate("*abc").unwrap().result);
+    /// assert!(!regex.evaluate("abc").unwrap().result);
     /// ```
     ///
-    pub fn evaluate(self, value: &str) -> Result<LineEvaluated, &str> {
-        if !value.is_ascii() {
-            return Err(RegexError::NoAsciiCharacter.message());
+    pub fn with_options(expression: &str, options: &RegexOptions) -> Result<Self, &'static str> {
+        let mut steps: Vec<RegexStep> = vec![];
+        let mut alternatives: Vec<Vec<RegexStep>> = vec![];
+        let mut open_groups: Vec<(usize, Vec<RegexStep>, Vec<Vec<RegexStep>>)> = vec![];
+        let mut groups = 0;
+        let mut last_was_repetition = false;
+
+        let mut chars_iter = expression.chars();
+        while let Some(c) = chars_iter.next() {
+            let leading = match c {
+                '*' | '+' | '?' => leading_literal(&mut steps, c, options),
+                _ => None,
+            };
+            let is_repetition = is_repetition_char(c) && leading.is_none();
+            if is_repetition && last_was_repetition {
+                return Err(RegexError::InvalidRepetition.message());
+            }
+            last_was_repetition = is_repetition;
+
+            let step = match c {
+                _ if leading.is_some() => leading,
+                '.' => point_char(),
+                '*' => wildcard_char(&mut steps),
+                '?' => option_char(&mut steps),
+                '+' => option_one_or_more_char(&mut steps),
+                '{' => repetition_char(&mut steps, &mut chars_iter)?,
+                '^' => anchor_start_char(&steps),
+                '$' => anchor_end_char(&chars_iter),
+                '[' => bracket_char(&mut chars_iter)?,
+                '\\' => escape_char(&mut chars_iter)?,
+                '(' => {
+                    groups += 1;
+                    open_groups.push((
+                        groups,
+                        std::mem::take(&mut steps),
+                        std::mem::take(&mut alternatives),
+                    ));
+                    None
+                }
+                ')' => {
+                    let (index, outer_steps, outer_alternatives) = match open_groups.pop() {
+                        Some(group) => group,
+                        None => return Err(RegexError::InvalidGroup.message()),
+                    };
+                    let inner_steps = std::mem::replace(&mut steps, outer_steps);
+                    let inner_alternatives =
+                        std::mem::replace(&mut alternatives, outer_alternatives);
+                    group_char(index, alternation_steps(inner_alternatives, inner_steps))
+                }
+                '|' => {
+                    alternatives.push(std::mem::take(&mut steps));
+                    None
+                }
+                _ => regular_char(c),
+            };
+
+            if let Some(s) = step {
+                steps.push(s);
+            }
+        }
+
+        if !open_groups.is_empty() {
+            return Err(RegexError::InvalidGroup.message());
+        }
+
+        // A lone quantifier matches any line, including an empty one, unless it is a literal
+        if let ("*" | "+" | "?", [step]) = (expression, steps.as_mut_slice()) {
+            if !options.literal_leading_quantifiers {
+                step.rep = RegexRep::Any;
+            }
+        }
+
+        // An empty alternative of the whole regex is ignored
+        if !alternatives.is_empty() {
+            alternatives.push(steps);
+            alternatives.retain(|alternative| !alternative.is_empty());
+            steps = alternatives.pop().unwrap_or_default();
+        }
+
+        // Without any step the regex would have nothing to match, so it is rejected
+        if steps.is_empty() {
+            return Err(RegexError::EmptyPattern.message());
+        }
+
+        Ok(Regex {
+            steps: alternation_steps(alternatives, steps),
+            options: options.clone(),
+            groups,
+            source: expression.to_string(),
+        })
+    }
+
+    /// Given a string, returns a new Regex that only matches if the whole string matches the regex,
+    /// as if the regex was between the anchors '^' and '$'
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - A string to be checked
+    ///
+    /// # Returns
+    ///
+    /// * Regex - The corresponding anchored Regex if the string is a valid regex
+    /// * &str - The corresponding error if the string is not a valid regex
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new_anchored("abc").unwrap();
+    ///
+    /// assert!(regex.evaluate("abc").unwrap().result);
+    /// assert!(!regex.evaluate("abcd").unwrap().result);
+    /// ```
+    ///
+    pub fn new_anchored(expression: &str) -> Result<Self, &str> {
+        let mut regex = Regex::try_from(expression)?;
+
+        let steps = std::mem::take(&mut regex.steps);
+        regex.steps = anchor_start_char(&[])
+            .into_iter()
+            .chain(steps)
+            .chain(anchor_end_char(&"".chars()))
+            .collect();
+
+        Ok(regex)
+    }
+
+    /// Given a string, returns a new Regex that matches the string literally,
+    /// without treating any of its characters as a metacharacter
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The literal text to be matched
+    ///
+    /// # Returns
+    ///
+    /// * Regex - The Regex made only of literal steps
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new_literal("a.c");
+    ///
+    /// assert!(regex.evaluate("xa.cx").unwrap().result);
+    /// assert!(!regex.evaluate("axc").unwrap().result);
+    /// ```
+    ///
+    pub fn new_literal(text: &str) -> Self {
+        let steps = text
+            .chars()
+            .map(|c| RegexStep {
+                rep: RegexRep::Exact(1),
+                val: RegexVal::Literal(c),
+                anchoring_start: false,
+                anchoring_end: false,
+            })
+            .collect();
+
+        Regex {
+            steps,
+            options: RegexOptions::default(),
+            groups: 0,
+            source: escape(text),
         }
+    }
+
+    /// Returns the pattern the regex was created from, exactly as it was written
+    ///
+    /// # Returns
+    ///
+    /// * &str - The original pattern of the regex
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("a.c").unwrap();
+    ///
+    /// assert_eq!(regex.pattern(), "a.c");
+    /// ```
+    ///
+    pub fn pattern(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the amount of top level steps of the regex.
+    /// A group or an alternation is a single step, whatever it contains.
+    ///
+    /// # Returns
+    ///
+    /// * usize - The amount of steps
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// assert_eq!(Regex::new("a.c").unwrap().step_count(), 3);
+    /// assert_eq!(Regex::new("a(bc)*").unwrap().step_count(), 2);
+    /// ```
+    ///
+    pub fn step_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns an iterator over read-only views of the top level steps of the regex, in order
+    ///
+    /// # Returns
+    ///
+    /// * impl Iterator<Item = StepView> - The view of each step
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::{Regex, StepKind};
+    ///
+    /// let regex = Regex::new("a.c").unwrap();
+    /// let kinds: Vec<StepKind> = regex.step_views().map(|step| step.kind()).collect();
+    ///
+    /// assert_eq!(kinds, vec![StepKind::Literal('a'), StepKind::Wildcard, StepKind::Literal('c')]);
+    /// ```
+    ///
+    pub fn step_views(&self) -> impl Iterator<Item = StepView<'_>> {
+        self.steps.iter().map(|step| StepView { step })
+    }
+
+    /// Returns if every match of the regex starts at the start of a line,
+    /// because the regex, or each of its alternatives, starts with the anchor '^'
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the regex is anchored at the start
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// assert!(Regex::new("^abc").unwrap().is_anchored_start());
+    /// assert!(!Regex::new("abc$").unwrap().is_anchored_start());
+    /// ```
+    ///
+    pub fn is_anchored_start(&self) -> bool {
+        is_anchored(&self.steps, <[RegexStep]>::first, |step| {
+            step.anchoring_start
+        })
+    }
+
+    /// Returns if every match of the regex ends at the end of a line,
+    /// because the regex, or each of its alternatives, ends with the anchor '$'
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the regex is anchored at the end
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// assert!(Regex::new("abc$").unwrap().is_anchored_end());
+    /// assert!(!Regex::new("^abc").unwrap().is_anchored_end());
+    /// ```
+    ///
+    pub fn is_anchored_end(&self) -> bool {
+        is_anchored(&self.steps, <[RegexStep]>::last, |step| step.anchoring_end)
+    }
+
+    /// Given a string, returns a LineEvaluated if the string matches the regex
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked
+    ///
+    /// # Returns
+    ///
+    /// * LineEvaluated - The result of the evaluation, with the length in bytes of the first match
+    /// * &str - The corresponding error if the string contains non-ascii characters,
+    ///   or if the evaluation exceeds the step limit of the regex
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("abc.*").unwrap();
+    /// let line = regex.evaluate("abcdefg").unwrap();
+    ///
+    /// assert_eq!(line.result, true);
+    /// assert_eq!(line.matched_len, 7);
+    /// ```
+    ///
+    pub fn evaluate(&self, value: &str) -> Result<LineEvaluated, &'static str> {
+        let matched_len = self.matched_len(value)?;
+
+        Ok(LineEvaluated {
+            result: matched_len.is_some(),
+            line: value.to_string(),
+            matched_len: matched_len.unwrap_or(0),
+        })
+    }
+
+    /// Given a string, returns if the string matches the regex
+    ///
+    fn is_match(&self, value: &str) -> Result<bool, &'static str> {
+        Ok(self.matched_len(value)?.is_some())
+    }
+
+    /// Given a string, returns the length in bytes of the first match of the regex, None if it does not match
+    ///
+    fn matched_len(&self, value: &str) -> Result<Option<usize>, &'static str> {
+        if !self.options.unicode && !value.is_ascii() {
+            return Err(RegexError::NoAsciiCharacter.message());
+        }
+
+        self.text_matched_len(value)
+    }
+
+    /// Given a string, returns the length in bytes of the first match of the regex without checking its characters
+    ///
+    fn text_matched_len(&self, value: &str) -> Result<Option<usize>, &'static str> {
+        let whole = self
+            .search_captures(value, 0)?
+            .and_then(|captures| captures.first().copied().flatten());
+
+        Ok(whole.map(|(start, end)| end - start))
+    }
+
+    /// Given a slice of bytes, returns if the bytes match the regex.
+    /// The bytes do not need to be valid UTF-8 or ASCII, each byte is matched as a single character.
+    /// ASCII bytes are matched as their character, and a byte from 0x80 to 0xFF only matches the point
+    /// or a negated bracket.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A slice of bytes to be checked
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the bytes match the regex
+    /// * &str - The corresponding error if the evaluation exceeds the step limit of the regex
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("a.c").unwrap();
+    ///
+    /// assert!(regex.evaluate_bytes(b"xa\xFFc").unwrap());
+    /// ```
+    ///
+    pub fn evaluate_bytes(&self, value: &[u8]) -> Result<bool, &'static str> {
+        let text: String = value.iter().map(|byte| byte_char(*byte)).collect();
+        Ok(self.text_matched_len(&text)?.is_some())
+    }
+
+    /// Given a text, returns the lines of the text that match the regex, in the order of the text.
+    /// The newline that ends the text is not followed by an empty line, as in the search of a file
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string whose lines are separated by a newline
+    ///
+    /// # Returns
+    ///
+    /// * Vec<String> - The lines that match the regex
+    /// * &str - The corresponding error if a line contains non-ascii characters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("ab.cd").unwrap();
+    /// let lines = regex.matching_lines("abcd\nabecd\nab10cd").unwrap();
+    ///
+    /// assert_eq!(lines, vec!["abecd"]);
+    /// ```
+    ///
+    pub fn matching_lines(&self, text: &str) -> Result<Vec<String>, &'static str> {
+        let mut lines = Vec::new();
+        for line in split_lines(text, '\n') {
+            if self.is_match(line)? {
+                lines.push(line.to_string());
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Given a string and a position, returns the first match of the regex that starts at or after the position
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked
+    /// * `position` - The byte position where the search starts
+    ///
+    /// # Returns
+    ///
+    /// * Option<RegexMatch> - The first match found, None if there is no match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("b.").unwrap();
+    /// let found = regex.find_at("abcabd", 2).unwrap();
+    ///
+    /// assert_eq!((found.start, found.end), (4, 6));
+    /// ```
+    ///
+    pub fn find_at(&self, value: &str, position: usize) -> Option<RegexMatch> {
+        let captures = self.captures_at(value, position)?;
+        let (start, end) = captures[0]?;
+
+        Some(RegexMatch { start, end })
+    }
+
+    /// Given a string and a position, returns the positions of the first match of the regex
+    /// that starts at or after the position, followed by the positions captured by each group.
+    /// A search that exceeds the step limit is considered as not matching.
+    ///
+    fn captures_at(&self, value: &str, position: usize) -> Option<Captures> {
+        self.search_captures(value, position).ok().flatten()
+    }
+
+    /// Given a string, returns if every literal that the regex requires at least once appears in it.
+    /// It avoids backtracking over long lines that can never match, like `a*a*a*b` over a line without `b`.
+    ///
+    fn has_required_literals(&self, value: &str) -> bool {
+        has_literals(&self.steps, value, &self.options)
+    }
+
+    /// Given a string and a position, returns the positions of the first match of the regex
+    /// that starts at or after the position, or the error if the search exceeds the step limit.
+    /// A regex anchored at the start is only tried at the start of each line.
+    ///
+    fn search_captures(
+        &self,
+        value: &str,
+        position: usize,
+    ) -> Result<Option<Captures>, &'static str> {
+        if self.steps.is_empty() || !self.has_required_literals(&value[position..]) {
+            return Ok(None);
+        }
+
+        let starts = value[position..]
+            .char_indices()
+            .map(|(i, _)| position + i)
+            .chain(std::iter::once(value.len()));
+
+        let matcher = Matcher::new(value, &self.options, false).memoizing();
+        let anchored = self.is_anchored_start();
+
+        for start in starts {
+            if anchored && !is_line_start(value, start, &self.options) {
+                // Without multiline only the start of the string can be the start of a line
+                if !self.options.multiline {
+                    break;
+                }
+                continue;
+            }
+
+            let mut captures = vec![None; self.groups + 1];
+            let found =
+                matcher.match_steps(&self.steps, start, &mut captures, &mut |end, _| Some(end));
+            matcher.check_limit()?;
+            if let Some(end) = found {
+                captures[0] = Some((start, end));
+                return Ok(Some(captures));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Given a string, returns the end of the shortest match of the regex that starts at the beginning of the string.
+    /// The steps are matched with the fewest repetitions first, and the ends are tried from the shortest
+    /// to the longest, so the first one the regex can reach is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked
+    ///
+    /// # Returns
+    ///
+    /// * Option<usize> - The byte position where the shortest match ends, None if the start does not match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("a+").unwrap();
+    ///
+    /// assert_eq!(regex.shortest_match("aaa"), Some(1));
+    /// assert_eq!(regex.shortest_match("baa"), None);
+    /// ```
+    ///
+    pub fn shortest_match(&self, value: &str) -> Option<usize> {
+        if self.steps.is_empty() {
+            return None;
+        }
+
+        let matcher = Matcher::new(value, &self.options, true);
+
+        (0..=value.len())
+            .filter(|end| value.is_char_boundary(*end))
+            .find(|end| {
+                let mut captures = vec![None; self.groups + 1];
+                let found = matcher.match_steps(&self.steps, 0, &mut captures, &mut |found, _| {
+                    (found == *end).then_some(found)
+                });
+                found.is_some()
+            })
+    }
+
+    /// Given a string, returns the substrings of the first match of the regex.
+    /// The first substring is the whole match, followed by the substring captured by each group,
+    /// None if the group did not participate in the match.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked
+    ///
+    /// # Returns
+    ///
+    /// * Option<Vec<Option<String>>> - The captured substrings, None if there is no match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("(a.)(c.)").unwrap();
+    /// let captures = regex.captures("axcy").unwrap();
+    ///
+    /// assert_eq!(captures[1], Some("ax".to_string()));
+    /// ```
+    ///
+    pub fn captures(&self, value: &str) -> Option<Vec<Option<String>>> {
+        let captures = self.captures_at(value, 0)?;
+
+        Some(
+            captures
+                .iter()
+                .map(|capture| capture.map(|(start, end)| value[start..end].to_string()))
+                .collect(),
+        )
+    }
+
+    /// Given a string, returns the first match of the regex
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked
+    ///
+    /// # Returns
+    ///
+    /// * Option<RegexMatch> - The first match found, None if there is no match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("c.*e").unwrap();
+    /// let found = regex.find("abcdef").unwrap();
+    ///
+    /// assert_eq!(found.as_str("abcdef"), "cde");
+    /// ```
+    ///
+    pub fn find(&self, value: &str) -> Option<RegexMatch> {
+        self.find_at(value, 0)
+    }
+
+    /// Given a string, returns an iterator over the non-overlapping matches of the regex
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked
+    ///
+    /// # Returns
+    ///
+    /// * Matches - An iterator of RegexMatch, from left to right
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("a.").unwrap();
+    /// let matches: Vec<&str> = regex.find_iter("abacad").map(|m| m.as_str("abacad")).collect();
+    ///
+    /// assert_eq!(matches, vec!["ab", "ac", "ad"]);
+    /// ```
+    ///
+    pub fn find_iter<'r, 'v>(&'r self, value: &'v str) -> Matches<'r, 'v> {
+        Matches {
+            regex: self,
+            value,
+            position: Some(0),
+        }
+    }
+
+    /// Given a string, returns an iterator over all the matches of the regex, including the overlapping ones.
+    /// Each match starts at a different position, and the search always advances one character
+    /// after the start of the previous match, so empty matches do not loop forever
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked
+    ///
+    /// # Returns
+    ///
+    /// * OverlappingMatches - An iterator of RegexMatch, from left to right
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("aa").unwrap();
+    /// let starts: Vec<usize> = regex.find_overlapping("aaaa").map(|m| m.start).collect();
+    ///
+    /// assert_eq!(starts, vec![0, 1, 2]);
+    /// ```
+    ///
+    pub fn find_overlapping<'r, 'v>(&'r self, value: &'v str) -> OverlappingMatches<'r, 'v> {
+        OverlappingMatches {
+            regex: self,
+            value,
+            position: Some(0),
+        }
+    }
+
+    /// Given a string, returns the substrings separated by the matches of the regex.
+    /// As with str::split, a match at the start or at the end produces an empty substring.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be split
+    ///
+    /// # Returns
+    ///
+    /// * Vec<String> - The substrings between the matches of the regex
+    /// * &str - The corresponding error if the string contains non-ascii characters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("[[:digit:]]+").unwrap();
+    /// let parts = regex.split("a1b22c").unwrap();
+    ///
+    /// assert_eq!(parts, vec!["a", "b", "c"]);
+    /// ```
+    ///
+    pub fn split(&self, value: &str) -> Result<Vec<String>, &'static str> {
+        if !self.options.unicode && !value.is_ascii() {
+            return Err(RegexError::NoAsciiCharacter.message());
+        }
+
+        let mut parts = Vec::new();
+        let mut last = 0;
+        for found in self.find_iter(value) {
+            parts.push(value[last..found.start].to_string());
+            last = found.end;
+        }
+        parts.push(value[last..].to_string());
+
+        Ok(parts)
+    }
+
+    /// Given a string and a replacement, returns the string with every non-overlapping match of the regex
+    /// replaced. In the replacement "$n" refers to the substring captured by the group n,
+    /// and "$$" is a literal '$'.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked
+    /// * `replacement` - The template that replaces each match
+    ///
+    /// # Returns
+    ///
+    /// * String - The string with the matches replaced
+    /// * &str - The corresponding error if the string contains non-ascii characters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::Regex;
+    ///
+    /// let regex = Regex::new("(a)(b)").unwrap();
+    /// let replaced = regex.replace_all("abab", "$2$1").unwrap();
+    ///
+    /// assert_eq!(replaced, "baba");
+    /// ```
+    ///
+    pub fn replace_all(&self, value: &str, replacement: &str) -> Result<String, &'static str> {
+        if !self.options.unicode && !value.is_ascii() {
+            return Err(RegexError::NoAsciiCharacter.message());
+        }
+
+        let mut result = String::new();
+        let mut last = 0;
+        let mut position = Some(0);
+        while let Some(captures) = position.and_then(|p| self.captures_at(value, p)) {
+            let (start, end) = captures[0].unwrap_or((last, last));
+            result.push_str(&value[last..start]);
+            expand_replacement(replacement, value, &captures, &mut result);
+            last = end;
+            position = position_after(value, start, end);
+        }
+        result.push_str(&value[last..]);
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex_builder::RegexBuilder;
+
+    /// Given a RegexVal and a RegexRep, returns the step that repeats the value
+    ///
+    fn step(val: RegexVal, rep: RegexRep) -> RegexStep {
+        RegexStep {
+            val,
+            rep,
+            anchoring_start: false,
+            anchoring_end: false,
+        }
+    }
+
+    /// Given some steps, returns the highest index of the groups inside them
+    ///
+    fn max_group(steps: &[RegexStep]) -> usize {
+        steps
+            .iter()
+            .map(|step| match &step.val {
+                RegexVal::Group { index, steps } => (*index).max(max_group(steps)),
+                RegexVal::Alternation(alternatives) => {
+                    alternatives.iter().map(|a| max_group(a)).max().unwrap_or(0)
+                }
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Given a single step and a string, runs only that step from the start of the string,
+    /// and returns the length in bytes of the match, None if the step does not match there.
+    /// The repetitions are greedy, so the longest match of the step is returned.
+    ///
+    fn step_match_len(step: &RegexStep, value: &str) -> Option<usize> {
+        let options = RegexOptions::default();
+        let matcher = Matcher::new(value, &options, false);
+        let steps = std::slice::from_ref(step);
+        let mut captures = vec![None; max_group(steps) + 1];
+
+        matcher.match_steps(steps, 0, &mut captures, &mut |end, _| Some(end))
+    }
+
+    #[test]
+    fn test_single_step_values() {
+        let one = || RegexRep::Exact(1);
+        let cases = [
+            (RegexVal::Literal('a'), "ab", Some(1)),
+            (RegexVal::Literal('a'), "ba", None),
+            (RegexVal::Wildcard, "xy", Some(1)),
+            (RegexVal::Wildcard, "", None),
+            (RegexVal::Class(RegexClass::Digit), "7a", Some(1)),
+            (RegexVal::Class(RegexClass::Digit), "a7", None),
+            (RegexVal::NotClass(RegexClass::Digit), "a7", Some(1)),
+            (RegexVal::NotClass(RegexClass::Digit), "7a", None),
+            (
+                RegexVal::Bracket(CharSet::from(vec!['x', 'y'])),
+                "yz",
+                Some(1),
+            ),
+            (RegexVal::Bracket(CharSet::from(vec!['x', 'y'])), "zy", None),
+            (
+                RegexVal::NotBracket(CharSet::from(vec!['x'])),
+                "zx",
+                Some(1),
+            ),
+            (RegexVal::NotBracket(CharSet::from(vec!['x'])), "xz", None),
+            (RegexVal::WordStart, "ab", Some(0)),
+            (RegexVal::WordStart, " ab", None),
+            (RegexVal::WordEnd, "", None),
+            (RegexVal::TextStart, "ab", Some(0)),
+            (RegexVal::TextEnd, "", Some(0)),
+            (RegexVal::TextEnd, "a", None),
+        ];
+
+        for (val, value, expected) in cases {
+            let description = format!("{:?} on {:?}", val, value);
+            assert_eq!(
+                step_match_len(&step(val, one()), value),
+                expected,
+                "{}",
+                description
+            );
+        }
+
+        let group = RegexVal::Group {
+            index: 1,
+            steps: vec![
+                step(RegexVal::Literal('a'), one()),
+                step(RegexVal::Literal('b'), one()),
+            ],
+        };
+        assert_eq!(step_match_len(&step(group.clone(), one()), "abc"), Some(2));
+        assert_eq!(step_match_len(&step(group, one()), "acb"), None);
+
+        let alternation = RegexVal::Alternation(vec![
+            vec![step(RegexVal::Literal('a'), one())],
+            vec![
+                step(RegexVal::Literal('b'), one()),
+                step(RegexVal::Literal('c'), one()),
+            ],
+        ]);
+        assert_eq!(
+            step_match_len(&step(alternation.clone(), one()), "bc"),
+            Some(2)
+        );
+        assert_eq!(
+            step_match_len(&step(alternation.clone(), one()), "ab"),
+            Some(1)
+        );
+        assert_eq!(step_match_len(&step(alternation, one()), "cb"), None);
+    }
+
+    #[test]
+    fn test_single_step_repetitions() {
+        let literal = || RegexVal::Literal('a');
+        let range = |min, max| RegexRep::Range { min, max };
+        let cases = [
+            (RegexRep::Any, "aaab", Some(3)),
+            (RegexRep::Any, "b", Some(0)),
+            (RegexRep::Exact(2), "aaab", Some(2)),
+            (RegexRep::Exact(2), "ab", None),
+            (RegexRep::Exact(0), "aaa", Some(0)),
+            (range(Some(1), None), "aaab", Some(3)),
+            (range(Some(1), None), "b", None),
+            (range(None, Some(1)), "aaab", Some(1)),
+            (range(None, Some(1)), "b", Some(0)),
+            (range(Some(2), Some(3)), "aaaab", Some(3)),
+            (range(Some(2), Some(3)), "ab", None),
+            (range(None, None), "aab", Some(2)),
+        ];
+
+        for (rep, value, expected) in cases {
+            let description = format!("{:?} on {:?}", rep, value);
+            assert_eq!(
+                step_match_len(&step(literal(), rep), value),
+                expected,
+                "{}",
+                description
+            );
+        }
+
+        let group = RegexVal::Group {
+            index: 1,
+            steps: vec![
+                step(RegexVal::Literal('a'), RegexRep::Exact(1)),
+                step(RegexVal::Literal('b'), RegexRep::Exact(1)),
+            ],
+        };
+        assert_eq!(
+            step_match_len(&step(group.clone(), RegexRep::Any), "ababa"),
+            Some(4)
+        );
+        assert_eq!(
+            step_match_len(&step(group, range(Some(3), None)), "ababa"),
+            None
+        );
+
+        let boundary = step(RegexVal::WordStart, RegexRep::Any);
+        assert_eq!(step_match_len(&boundary, " a"), Some(0));
+    }
+
+    #[test]
+    fn test_ascii() {
+        let value = "abacdef";
+
+        let regex = Regex::new("ab.*c").unwrap();
+
+        let matches = regex.evaluate(value);
+        assert!(matches.is_ok());
+        let line = matches.unwrap();
+        assert!(line.result);
+    }
+
+    #[test]
+    fn test_no_ascii() {
+        let value = "abacdதிf";
+
+        let regex = Regex::new("ab.*c").unwrap();
+
+        let matches = regex.evaluate(value);
+        assert!(matches.is_err());
+        assert_eq!(
+            matches.unwrap_err().to_string(),
+            RegexError::NoAsciiCharacter.message()
+        );
+    }
+
+    #[test]
+    fn test_match_point() -> Result<(), &'static str> {
+        let value = "abcdefg";
+
+        let regex = Regex::new(".").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_multiple_points() -> Result<(), &'static str> {
+        let value = "abcdefg";
+
+        let regex = Regex::new("...").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_more_points_than_letters() -> Result<(), &'static str> {
+        let value = "abc";
+
+        let regex = Regex::new("....").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_literal() -> Result<(), &'static str> {
+        let value = "abcdef";
+
+        let regex = Regex::new("a").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_multiple_literal() -> Result<(), &'static str> {
+        let value = "abcdef";
+
+        let regex = Regex::new("abc").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_middle_literals() -> Result<(), &'static str> {
+        let value = "abcdef";
+
+        let regex = Regex::new("cde").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_middle_literals() -> Result<(), &'static str> {
+        let value = "abcdef";
+
+        let regex = Regex::new("ce").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_literal_and_point() -> Result<(), &'static str> {
+        let value = "abcdef";
+
+        let regex = Regex::new("a.c").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_literal_and_point() -> Result<(), &'static str> {
+        let value = "abcdef";
+
+        let regex = Regex::new("a.d").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_multiple_literal_and_point() -> Result<(), &'static str> {
+        let value = "abcdefghijk";
+
+        let regex = Regex::new("c..f..i").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_point_and_asterisk() -> Result<(), &'static str> {
+        let value = "abcdef";
+
+        let regex = Regex::new("ab.*e").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_point_and_asterisk() -> Result<(), &'static str> {
+        let value = "abcdef";
+
+        let regex = Regex::new("ab.*h").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_2_point_and_asterisk() -> Result<(), &'static str> {
+        let value = "ab1234cdefg";
+
+        let regex = Regex::new("ab.*c.*f").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_2_point_and_asterisk() -> Result<(), &'static str> {
+        let value = "ab1234cdegh";
+
+        let regex = Regex::new("ab.*c.*f").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_literal_and_asterisk() -> Result<(), &'static str> {
+        let value = "ab111cde";
+
+        let regex = Regex::new("ab1*").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_literal_and_asterisk() -> Result<(), &'static str> {
+        let value = "ab111cde";
+
+        let regex = Regex::new("ab2*").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_multiple_literal_and_asterisk() -> Result<(), &'static str> {
+        let value = "ab111cde";
+
+        let regex = Regex::new("ab2*g*3*").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_single_asterisk() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new("*").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_single_point_and_asterisk() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new(".*").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_point_and_asterisk_at_start() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new(".*abcd").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_single_asterisk_and_literal() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new(".*fgh").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_question_mark() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new("abcd?").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_question_mark() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new("abcr?").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_question_mark_and_point() -> Result<(), &'static str> {
+        let value = "abd";
+
+        let regex = Regex::new("ab.?d").unwrap();
+        println!("{:?}", regex);
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_question_mark_and_literal() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new("abc?de.g.*").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_single_plus() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new("abcd+").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_plus() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new("abce+").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_multiple_literal_plus() -> Result<(), &'static str> {
+        let value = "abcddddddddef";
+
+        let regex = Regex::new("abcd+").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_question_mark_literal_and_plus() -> Result<(), &'static str> {
+        let value = "abcdefghijklllllllm";
+
+        let regex = Regex::new("abc?de.g.*l+").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_middle_repetition() -> Result<(), &'static str> {
+        let value = "abcccccdeeeeeefghij";
+
+        let regex = Regex::new("c*de+fg.i?").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_only_plus() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new("+").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_only_point_and_plus() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new(".+").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_only_question_mark() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new("?").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_only_point_and_question_mark() -> Result<(), &'static str> {
+        let value = "abcdefghij";
+
+        let regex = Regex::new(".?").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_empty_line() -> Result<(), &'static str> {
+        let value = "";
+
+        let regex1 = Regex::new("*").unwrap();
+        let regex2 = Regex::new("+").unwrap();
+        let regex3 = Regex::new("?").unwrap();
+
+        let line1 = regex1.evaluate(value)?;
+        let line2 = regex2.evaluate(value)?;
+        let line3 = regex3.evaluate(value)?;
+
+        assert!(line1.result);
+        assert!(line2.result);
+        assert!(line3.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_start_with_repetition() -> Result<(), &'static str> {
+        let value = "testeo";
+
+        let regex1 = Regex::new("*esteo").unwrap();
+        let regex2 = Regex::new("+esteo").unwrap();
+        let regex3 = Regex::new("?esteo").unwrap();
+
+        let line1 = regex1.evaluate(value)?;
+        let line2 = regex2.evaluate(value)?;
+        let line3 = regex3.evaluate(value)?;
+
+        assert!(line1.result);
+        assert!(line2.result);
+        assert!(line3.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_start_with_exact_repetition() -> Result<(), &'static str> {
+        let regex = Regex::new("{2}esteo")?;
+
+        assert!(!regex.evaluate("testeo")?.result);
+        assert!(regex.evaluate("ttesteo")?.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_leading_quantifiers_apply_to_point() -> Result<(), &'static str> {
+        for (leading, with_point) in [
+            ("*a", ".*a"),
+            ("+a", ".+a"),
+            ("?a", ".?a"),
+            ("{2}a", ".{2}a"),
+            ("{1,3}a", ".{1,3}a"),
+            ("^*a", "^.*a"),
+            ("^{2}a", "^.{2}a"),
+        ] {
+            let leading_steps = format!("{:?}", Regex::new(leading)?.steps);
+            let point_steps = format!("{:?}", Regex::new(with_point)?.steps);
+            assert_eq!(leading_steps, point_steps, "{}", leading);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_range_combination_with_start_and_end() -> Result<(), &'static str> {
+        let value = "abccccc";
+
+        let regex = Regex::new("abc{2,10}").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_range_combination_with_start_and_end() -> Result<(), &'static str> {
+        let value = "abc";
+
+        let regex = Regex::new("abc{2,10}").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_range_combination_exact() -> Result<(), &'static str> {
+        let value1 = "abccccc33";
+        let value2 = "aaa";
+
+        let regex1 = Regex::new("abc{5}").unwrap();
+        let regex2 = Regex::new("a{3}").unwrap();
+
+        let line = regex1.evaluate(value1)?;
+        assert!(line.result);
+        let line = regex2.evaluate(value2)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_range_combination_exact() -> Result<(), &'static str> {
+        let value = "abcc33";
+
+        let regex = Regex::new("abc{5}").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_range_combination_exact_2() -> Result<(), &'static str> {
+        let value = "abcccccc33";
+
+        let regex = Regex::new("abc{5}3").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_range_combination_only_start() -> Result<(), &'static str> {
+        let value = "abccccc";
+
+        let regex = Regex::new("abc{2,}").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_range_combination_only_start() -> Result<(), &'static str> {
+        let value = "abc";
+
+        let regex = Regex::new("abc{2,}").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_range_only_start_followed_by_steps() -> Result<(), &'static str> {
+        let regex = Regex::new("a{2,}b")?;
+
+        assert!(regex.evaluate("aaab")?.result);
+        assert_eq!(regex.find("xaaaab").map(|m| (m.start, m.end)), Some((1, 6)));
+        assert!(!regex.evaluate("ab")?.result);
+
+        let regex = Regex::new("a{2,}ab")?;
+
+        assert!(regex.evaluate("aaab")?.result);
+        assert!(!regex.evaluate("aab")?.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_range_from_zero_requires_following_steps() -> Result<(), &'static str> {
+        let regex = Regex::new("ab{0,2}c")?;
+
+        assert!(regex.evaluate("ac")?.result);
+        assert!(regex.evaluate("abc")?.result);
+        assert!(regex.evaluate("abbc")?.result);
+        assert!(!regex.evaluate("abbbc")?.result);
+        assert!(!regex.evaluate("ab")?.result);
+        assert!(!regex.evaluate("a")?.result);
+
+        let regex = Regex::new("ab?c")?;
+
+        assert!(regex.evaluate("ac")?.result);
+        assert!(!regex.evaluate("abbc")?.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_range_combination_only_end() -> Result<(), &'static str> {
+        let value = "abcccd";
+
+        let regex = Regex::new("abc{,5}").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_range_combination_only_end() -> Result<(), &'static str> {
+        let value = "abccccccd";
+
+        let regex = Regex::new("abc{,5}d").unwrap();
+
+        let line = regex.evaluate(value)?;
+        assert!(!line.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backslash_basic() -> Result<(), &'static str> {
+        let value1 = "bca.bc";
+        let regex1 = Regex::new("a\\.").unwrap();
+        let line1 = regex1.evaluate(value1)?;
+        assert!(line1.result);
+
+        let value2 = "bcabc";
+        let regex2 = Regex::new("a\\.").unwrap();
+        let line2 = regex2.evaluate(value2)?;
+        assert!(!line2.result);
+
+        let value3 = "{abc";
+        let regex3 = Regex::new("\\{abc").unwrap();
+        let line3 = regex3.evaluate(value3)?;
+        assert!(line3.result);
+
+        let value4 = "abc";
+        let regex4 = Regex::new("\\abc").unwrap();
+        let line4 = regex4.evaluate(value4)?;
+        assert!(line4.result);
+
+        let value5 = ".e+e?e";
+        let regex5 = Regex::new("\\.e\\+e\\?e").unwrap();
+        let line5 = regex5.evaluate(value5)?;
+        assert!(line5.result);
+
+        let regex6 = Regex::new("abc\\").unwrap_err();
+        assert_eq!(regex6, "Invalid regex: invalid backslash");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backslash_backslash() -> Result<(), &'static str> {
+        let value1 = "bca\\bc";
+        let regex1 = Regex::new("a\\\\b").unwrap();
+        let line1 = regex1.evaluate(value1)?;
+        assert!(line1.result);
+
+        let value2 = "bcabc";
+        let regex2 = Regex::new("a\\\\b").unwrap();
+        let line2 = regex2.evaluate(value2)?;
+        assert!(!line2.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_anchoring_start() -> Result<(), &'static str> {
+        let value1 = "start middle end";
+        let value2 = "start with start";
+        let value3 = "end with end";
+        let value4 = "only this line";
+
+        let regex = Regex::new("^start").unwrap();
+
+        let line1 = regex.evaluate(value1)?;
+        let line2 = regex.evaluate(value2)?;
+        let line3 = regex.evaluate(value3)?;
+        let line4 = regex.evaluate(value4)?;
+
+        assert!(line1.result);
+        assert!(line2.result);
+        assert!(!line3.result);
+        assert!(!line4.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_anchoring_end() -> Result<(), &'static str> {
+        let value1 = "start middle end";
+        let value2 = "start with start";
+        let value3 = "end with end";
+        let value4 = "only this line";
 
-        let mut queue = VecDeque::from(self.steps);
-        let queue_size = queue.len();
-        let mut state = false;
+        let regex = Regex::new("end$").unwrap();
 
-        if queue_size == 1 && value.is_empty() {
-            if let Some(step) = queue.pop_front() {
-                match step.val {
-                    RegexVal::Wildcard => {
-                        state = true;
-                    }
-                    _ => {
-                        queue.push_front(step);
-                    }
-                }
-            }
-        }
+        let line1 = regex.evaluate(value1)?;
+        let line2 = regex.evaluate(value2)?;
+        let line3 = regex.evaluate(value3)?;
+        let line4 = regex.evaluate(value4)?;
 
-        evaluate_step(&mut queue, value, state, queue_size)
+        assert!(line1.result);
+        assert!(!line2.result);
+        assert!(line3.result);
+        assert!(!line4.result);
+
+        Ok(())
     }
-}
 
-fn backtrack(
-    current: RegexStep,
-    evaluated: &mut Vec<EvaluatedStep>,
-    next: &mut VecDeque<RegexStep>,
-) -> Option<usize> {
-    let mut back_size = 0;
-    next.push_front(current);
-
-    while let Some(e) = evaluated.pop() {
-        back_size += e.match_size;
-        if e.backtrackable {
-            return Some(back_size);
-        } else {
-            next.push_front(e.step);
-        }
+    #[test]
+    fn test_anchoring_fails() -> Result<(), &'static str> {
+        let value1 = "start middle end";
+        let regex1 = Regex::new("^middle").unwrap();
+        let line1 = regex1.evaluate(value1)?;
+        assert!(!line1.result);
+
+        let value2 = "start middle end";
+        let regex2 = Regex::new("middle$").unwrap();
+        let line2 = regex2.evaluate(value2)?;
+        assert!(!line2.result);
+
+        Ok(())
     }
-    None
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_bracket_expressions() -> Result<(), &'static str> {
+        let value1 = "abc";
+        let value2 = "acc";
+        let value3 = "azc";
+        let value4 = "a9c";
+        let value5 = "a3";
+        let value6 = "ae";
+        let value7 = "aec";
+        let value8 = "aaaaaaeccccccc";
+        let value9 = "aabcdefc";
+
+        let regex = Regex::new("a[abcdef]c").unwrap();
+
+        let line1 = regex.evaluate(value1)?;
+        let line2 = regex.evaluate(value2)?;
+        let line3 = regex.evaluate(value3)?;
+        let line4 = regex.evaluate(value4)?;
+        let line5 = regex.evaluate(value5)?;
+        let line6 = regex.evaluate(value6)?;
+        let line7 = regex.evaluate(value7)?;
+        let line8 = regex.evaluate(value8)?;
+        let line9 = regex.evaluate(value9)?;
+
+        assert!(line1.result);
+        assert!(line2.result);
+        assert!(!line3.result);
+        assert!(!line4.result);
+        assert!(!line5.result);
+        assert!(!line6.result);
+        assert!(line7.result);
+        assert!(line8.result);
+        assert!(line9.result);
+
+        Ok(())
+    }
 
     #[test]
-    fn test_ascii() {
-        let value = "abacdef";
+    fn test_negated_bracket_expressions() -> Result<(), &'static str> {
+        let value1 = "abc";
+        let value2 = "acc";
+        let value3 = "azc";
+        let value4 = "a9c";
+        let value5 = "a3";
+        let value6 = "ae";
+        let value7 = "aec";
+        let value8 = "aaaaaaeccccccc";
+        let value9 = "ahcalcazcakc";
 
-        let regex = Regex::new("ab.*c").unwrap();
+        let regex = Regex::new("a[^ghijkl]c").unwrap();
 
-        let matches = regex.evaluate(value);
-        assert!(matches.is_ok());
-        let line = matches.unwrap();
-        assert!(line.result);
+        let line1 = regex.evaluate(value1)?;
+        let line2 = regex.evaluate(value2)?;
+        let line3 = regex.evaluate(value3)?;
+        let line4 = regex.evaluate(value4)?;
+        let line5 = regex.evaluate(value5)?;
+        let line6 = regex.evaluate(value6)?;
+        let line7 = regex.evaluate(value7)?;
+        let line8 = regex.evaluate(value8)?;
+        let line9 = regex.evaluate(value9)?;
+
+        assert!(line1.result);
+        assert!(line2.result);
+        assert!(line3.result);
+        assert!(line4.result);
+        assert!(!line5.result);
+        assert!(!line6.result);
+        assert!(line7.result);
+        assert!(line8.result);
+        assert!(line9.result);
+
+        Ok(())
     }
 
     #[test]
-    fn test_no_ascii() {
-        let value = "abacdதிf";
+    fn test_big_bracket_expressions() -> Result<(), &'static str> {
+        // Brackets are stored in a bitset, the results are the same as with a list of chars
+        let members = "abcdefghijklmnopqrstuvwxyz0123456789";
+        let regex = Regex::new(&format!("^[{}]+$", members)).unwrap();
+        assert!(regex.evaluate(members)?.result);
+        assert!(!regex.evaluate("abcD")?.result);
+        assert!(!regex.evaluate("ab_c")?.result);
+
+        let regex = Regex::new(&format!("[^{}]", members)).unwrap();
+        assert_eq!(regex.find("abc9_z").map(|m| m.start), Some(4));
+        assert!(regex.find(members).is_none());
+
+        let regex = RegexBuilder::new("[xyz\u{e9}]")
+            .unicode(true)
+            .case_insensitive(true)
+            .build()?;
+        assert!(regex.evaluate("\u{c9}")?.result);
+        assert!(regex.evaluate("Y")?.result);
+        assert!(!regex.evaluate("a")?.result);
 
-        let regex = Regex::new("ab.*c").unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn test_bracket_ranges() -> Result<(), &'static str> {
+        let regex = Regex::new("^[a-c0-9]+$").unwrap();
+        assert!(regex.evaluate("abc019")?.result);
+        assert!(!regex.evaluate("abd")?.result);
+        assert!(!regex.evaluate("a-c")?.result);
+
+        let regex = Regex::new("[-a]").unwrap();
+        assert!(regex.evaluate("-")?.result);
+        assert!(!regex.evaluate("b")?.result);
+
+        let regex = Regex::new("[a-]").unwrap();
+        assert!(regex.evaluate("-")?.result);
+        assert!(!regex.evaluate("b")?.result);
 
-        let matches = regex.evaluate(value);
-        assert!(matches.is_err());
         assert_eq!(
-            matches.unwrap_err().to_string(),
-            RegexError::NoAsciiCharacter.message()
+            Regex::new("[z-a]").unwrap_err(),
+            RegexError::InvalidRange.message()
         );
+
+        Ok(())
     }
 
     #[test]
-    fn test_match_point() -> Result<(), &'static str> {
-        let value = "abcdefg";
+    fn test_bracket_ranges_with_escaped_members() -> Result<(), &'static str> {
+        let regex = Regex::new("[\\]]").unwrap();
+        assert!(regex.evaluate("]")?.result);
+        assert!(!regex.evaluate("\\")?.result);
+
+        let regex = Regex::new("^[a\\-c]$").unwrap();
+        assert!(regex.evaluate("a")?.result);
+        assert!(regex.evaluate("-")?.result);
+        assert!(regex.evaluate("c")?.result);
+        assert!(!regex.evaluate("b")?.result);
+
+        let regex = Regex::new("^[\\-a]$").unwrap();
+        assert!(regex.evaluate("-")?.result);
+        assert!(regex.evaluate("a")?.result);
+        assert!(!regex.evaluate(".")?.result);
+
+        let regex = Regex::new("^[\\]-a]$").unwrap();
+        assert!(regex.evaluate("]")?.result);
+        assert!(regex.evaluate("^")?.result);
+        assert!(regex.evaluate("a")?.result);
+        assert!(!regex.evaluate("b")?.result);
 
-        let regex = Regex::new(".").unwrap();
+        Ok(())
+    }
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+    #[test]
+    fn test_dangling_brackets() {
+        let error = RegexError::InvalidBracket.message();
+
+        assert_eq!(Regex::new("a[").unwrap_err(), error);
+        assert_eq!(Regex::new("[abc").unwrap_err(), error);
+        assert_eq!(Regex::new("[]").unwrap_err(), error);
+        assert_eq!(Regex::new("[^]").unwrap_err(), error);
+    }
+
+    #[test]
+    fn test_closing_bracket_first_in_bracket() -> Result<(), &'static str> {
+        let regex = Regex::new("a[]b]").unwrap();
+        assert!(regex.evaluate("a]")?.result);
+        assert!(regex.evaluate("ab")?.result);
+        assert!(!regex.evaluate("ac")?.result);
+
+        let regex = Regex::new("a[^]b]").unwrap();
+        assert!(!regex.evaluate("a]")?.result);
+        assert!(!regex.evaluate("ab")?.result);
+        assert!(regex.evaluate("ac")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_multiple_points() -> Result<(), &'static str> {
-        let value = "abcdefg";
+    fn test_single_negated_bracket_needs_a_character() -> Result<(), &'static str> {
+        let regex = Regex::new("a[^b]").unwrap();
 
-        let regex = Regex::new("...").unwrap();
+        assert!(regex.evaluate("ax")?.result);
+        assert!(!regex.evaluate("ab")?.result);
+        assert!(!regex.evaluate("a")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = Regex::new("[^a]").unwrap();
+        assert!(regex.find("").is_none());
+        assert!(regex.find("aaa").is_none());
+        assert!(!regex.evaluate("")?.result);
 
         Ok(())
     }
 
+    const VALUE1: &str = "abc";
+    const VALUE2: &str = "a1c";
+    const VALUE3: &str = "a%c";
+    const VALUE4: &str = "aBc";
+    const VALUE5: &str = "a c";
+    const VALUE6: &str = "a-c";
+
     #[test]
-    fn test_match_more_points_than_letters() -> Result<(), &'static str> {
-        let value = "abc";
+    fn test_regex_alnum_class() -> Result<(), &'static str> {
+        // Alphanumeric
+        let alnum_regex = Regex::new("a[[:alnum:]]c").unwrap();
 
-        let regex = Regex::new("....").unwrap();
+        let alnum_line1 = alnum_regex.evaluate(VALUE1)?;
+        let alnum_line2 = alnum_regex.evaluate(VALUE2)?;
+        let alnum_line3 = alnum_regex.evaluate(VALUE3)?;
+        let alnum_line4 = alnum_regex.evaluate(VALUE4)?;
+        let alnum_line5 = alnum_regex.evaluate(VALUE5)?;
+        let alnum_line6 = alnum_regex.evaluate(VALUE6)?;
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+        assert!(alnum_line1.result);
+        assert!(alnum_line2.result);
+        assert!(!alnum_line3.result);
+        assert!(alnum_line4.result);
+        assert!(!alnum_line5.result);
+        assert!(!alnum_line6.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_literal() -> Result<(), &'static str> {
-        let value = "abcdef";
+    fn test_regex_alpha_class() -> Result<(), &'static str> {
+        // Alphabetic
+        let alpha_regex = Regex::new("a[[:alpha:]]c").unwrap();
 
-        let regex = Regex::new("a").unwrap();
+        let alpha_line1 = alpha_regex.evaluate(VALUE1)?;
+        let alpha_line2 = alpha_regex.evaluate(VALUE2)?;
+        let alpha_line3 = alpha_regex.evaluate(VALUE3)?;
+        let alpha_line4 = alpha_regex.evaluate(VALUE4)?;
+        let alpha_line5 = alpha_regex.evaluate(VALUE5)?;
+        let alpha_line6 = alpha_regex.evaluate(VALUE6)?;
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert!(alpha_line1.result);
+        assert!(!alpha_line2.result);
+        assert!(!alpha_line3.result);
+        assert!(alpha_line4.result);
+        assert!(!alpha_line5.result);
+        assert!(!alpha_line6.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_multiple_literal() -> Result<(), &'static str> {
-        let value = "abcdef";
+    fn test_regex_digit_class() -> Result<(), &'static str> {
+        // Digit - Numeric
+        let digit_regex = Regex::new("a[[:digit:]]c").unwrap();
 
-        let regex = Regex::new("abc").unwrap();
+        let digit_line1 = digit_regex.evaluate(VALUE1)?;
+        let digit_line2 = digit_regex.evaluate(VALUE2)?;
+        let digit_line3 = digit_regex.evaluate(VALUE3)?;
+        let digit_line4 = digit_regex.evaluate(VALUE4)?;
+        let digit_line5 = digit_regex.evaluate(VALUE5)?;
+        let digit_line6 = digit_regex.evaluate(VALUE6)?;
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert!(!digit_line1.result);
+        assert!(digit_line2.result);
+        assert!(!digit_line3.result);
+        assert!(!digit_line4.result);
+        assert!(!digit_line5.result);
+        assert!(!digit_line6.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_middle_literals() -> Result<(), &'static str> {
-        let value = "abcdef";
+    fn test_regex_lower_class() -> Result<(), &'static str> {
+        // Lowercase letters
+        let lower_regex = Regex::new("a[[:lower:]]c").unwrap();
 
-        let regex = Regex::new("cde").unwrap();
+        let lower_line1 = lower_regex.evaluate(VALUE1)?;
+        let lower_line2 = lower_regex.evaluate(VALUE2)?;
+        let lower_line3 = lower_regex.evaluate(VALUE3)?;
+        let lower_line4 = lower_regex.evaluate(VALUE4)?;
+        let lower_line5 = lower_regex.evaluate(VALUE5)?;
+        let lower_line6 = lower_regex.evaluate(VALUE6)?;
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert!(lower_line1.result);
+        assert!(!lower_line2.result);
+        assert!(!lower_line3.result);
+        assert!(!lower_line4.result);
+        assert!(!lower_line5.result);
+        assert!(!lower_line6.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_no_match_middle_literals() -> Result<(), &'static str> {
-        let value = "abcdef";
+    fn test_regex_upper_class() -> Result<(), &'static str> {
+        // Uppercase letters
+        let upper_regex = Regex::new("a[[:upper:]]c").unwrap();
 
-        let regex = Regex::new("ce").unwrap();
+        let upper_line1 = upper_regex.evaluate(VALUE1)?;
+        let upper_line2 = upper_regex.evaluate(VALUE2)?;
+        let upper_line3 = upper_regex.evaluate(VALUE3)?;
+        let upper_line4 = upper_regex.evaluate(VALUE4)?;
+        let upper_line5 = upper_regex.evaluate(VALUE5)?;
+        let upper_line6 = upper_regex.evaluate(VALUE6)?;
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+        assert!(!upper_line1.result);
+        assert!(!upper_line2.result);
+        assert!(!upper_line3.result);
+        assert!(upper_line4.result);
+        assert!(!upper_line5.result);
+        assert!(!upper_line6.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_literal_and_point() -> Result<(), &'static str> {
-        let value = "abcdef";
+    fn test_regex_space_class() -> Result<(), &'static str> {
+        // Space character
+        let space_regex = Regex::new("a[[:space:]]c").unwrap();
 
-        let regex = Regex::new("a.c").unwrap();
+        let space_line1 = space_regex.evaluate(VALUE1)?;
+        let space_line2 = space_regex.evaluate(VALUE2)?;
+        let space_line3 = space_regex.evaluate(VALUE3)?;
+        let space_line4 = space_regex.evaluate(VALUE4)?;
+        let space_line5 = space_regex.evaluate(VALUE5)?;
+        let space_line6 = space_regex.evaluate(VALUE6)?;
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert!(!space_line1.result);
+        assert!(!space_line2.result);
+        assert!(!space_line3.result);
+        assert!(!space_line4.result);
+        assert!(space_line5.result);
+        assert!(!space_line6.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_regex_punct_class() -> Result<(), &'static str> {
+        // Punctuation character
+        let punct_regex = Regex::new("a[[:punct:]]c").unwrap();
+
+        let punct_line1 = punct_regex.evaluate(VALUE1)?;
+        let punct_line2 = punct_regex.evaluate(VALUE2)?;
+        let punct_line3 = punct_regex.evaluate(VALUE3)?;
+        let punct_line4 = punct_regex.evaluate(VALUE4)?;
+        let punct_line5 = punct_regex.evaluate(VALUE5)?;
+        let punct_line6 = punct_regex.evaluate(VALUE6)?;
+
+        assert!(!punct_line1.result);
+        assert!(!punct_line2.result);
+        assert!(punct_line3.result);
+        assert!(!punct_line4.result);
+        assert!(!punct_line5.result);
+        assert!(punct_line6.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_classes() -> Result<(), &'static str> {
+        let malformed = [
+            "[[:alpha]",
+            "a[[:alpha]c",
+            "[[:alph:]]",
+            "[[:alpha:",
+            "[[:alpha",
+            "[[:al:pha:]]",
+        ];
+
+        for pattern in malformed {
+            assert_eq!(
+                Regex::new(pattern).unwrap_err(),
+                RegexError::InvalidClass.message()
+            );
+        }
+
+        assert!(Regex::new("a[[:alpha:]]c")?.evaluate("abc")?.result);
+
+        let regex = Regex::new("x[[ab]y")?;
+        assert!(regex.evaluate("x[y")?.result);
+        assert!(regex.evaluate("xay")?.result);
+        assert!(!regex.evaluate("xcy")?.result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_repetition_bounds() -> Result<(), &'static str> {
+        for pattern in ["a{0,0}b", "a{,0}b", "a{0}b"] {
+            let found = Regex::new(pattern)?.find("aaab").map(|m| (m.start, m.end));
+            assert_eq!(found, Some((3, 4)), "{}", pattern);
+        }
 
+        let found = Regex::new("a{0,}b")?.find("aaab").map(|m| (m.start, m.end));
+        assert_eq!(found, Some((0, 4)));
+
+        for pattern in [
+            "a{3,2}",
+            "a{1,0}",
+            "a{99999999999999999999999}",
+            "a{1,99999999999999999999999}",
+        ] {
+            assert_eq!(
+                Regex::new(pattern).unwrap_err(),
+                RegexError::InvalidRange.message(),
+                "{}",
+                pattern
+            );
+        }
         Ok(())
     }
 
     #[test]
-    fn test_no_match_literal_and_point() -> Result<(), &'static str> {
-        let value = "abcdef";
+    fn test_negated_and_mixed_classes() -> Result<(), &'static str> {
+        let regex = Regex::new("^[^[:digit:]]+$")?;
+        assert!(regex.evaluate("abc")?.result);
+        assert!(!regex.evaluate("ab1")?.result);
 
-        let regex = Regex::new("a.d").unwrap();
+        let regex = Regex::new("^[[:alpha:]x0-2]+$")?;
+        assert!(regex.evaluate("ab1x0")?.result);
+        assert!(!regex.evaluate("ab3")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+        let regex = Regex::new("^[[:digit:][:space:]_]+$")?;
+        assert!(regex.evaluate("1 2_3")?.result);
+        assert!(!regex.evaluate("1a")?.result);
 
+        let regex = Regex::new("a[^[:alpha:]-]c")?;
+        assert!(regex.evaluate("a1c")?.result);
+        assert!(!regex.evaluate("a-c")?.result);
+        assert!(!regex.evaluate("abc")?.result);
+
+        assert_eq!(
+            Regex::new("[^[::]]").unwrap_err(),
+            RegexError::InvalidClass.message()
+        );
         Ok(())
     }
 
     #[test]
-    fn test_match_multiple_literal_and_point() -> Result<(), &'static str> {
-        let value = "abcdefghijk";
+    fn test_empty_class_names() {
+        let malformed = [
+            "[[::]]",
+            "[[:]]",
+            "[[:  :]]",
+            "[[:\t:]]",
+            "[[: alpha :]]",
+            "a[[::]]b",
+            "[[:",
+            "[[:]",
+            "[[::",
+            "[[::]",
+        ];
+
+        for pattern in malformed {
+            assert_eq!(
+                Regex::new(pattern).unwrap_err(),
+                RegexError::InvalidClass.message(),
+                "{}",
+                pattern
+            );
+        }
+    }
 
-        let regex = Regex::new("c..f..i").unwrap();
+    #[test]
+    fn test_escaped_pipe_is_literal() -> Result<(), &'static str> {
+        let regex = Regex::new("a\\|b")?;
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert!(regex.evaluate("a|b")?.result);
+        assert!(regex.evaluate("xa|bx")?.result);
+        assert!(!regex.evaluate("a")?.result);
+        assert!(!regex.evaluate("b")?.result);
+
+        let regex = Regex::new("a|b")?;
+
+        assert!(regex.evaluate("a")?.result);
+        assert!(regex.evaluate("b")?.result);
+        assert!(!regex.evaluate("c")?.result);
+
+        let regex = Regex::new("(x\\|y|z)w")?;
+        let found = regex.find("zw x|yw").unwrap();
+        assert_eq!((found.start, found.end), (0, 2));
+        let found = regex.find("x|yw").unwrap();
+        assert_eq!((found.start, found.end), (0, 4));
+        assert!(regex.find("yw").is_none());
 
         Ok(())
     }
 
     #[test]
-    fn test_match_point_and_asterisk() -> Result<(), &'static str> {
-        let value = "abcdef";
-
-        let regex = Regex::new("ab.*e").unwrap();
+    fn test_escaped_b_in_bracket_is_literal() -> Result<(), &'static str> {
+        let regex = Regex::new("a[\\b]c")?;
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert!(regex.evaluate("abc")?.result);
+        assert!(!regex.evaluate("a\u{8}c")?.result);
+        assert!(!regex.evaluate("a\\c")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_no_match_point_and_asterisk() -> Result<(), &'static str> {
-        let value = "abcdef";
+    fn test_find_match_position() {
+        let regex = Regex::new("c.e").unwrap();
 
-        let regex = Regex::new("ab.*h").unwrap();
-
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+        let found = regex.find("abcdef").unwrap();
+        assert_eq!(found, RegexMatch { start: 2, end: 5 });
 
-        Ok(())
+        assert!(regex.find("abcdf").is_none());
     }
 
     #[test]
-    fn test_match_2_point_and_asterisk() -> Result<(), &'static str> {
-        let value = "ab1234cdefg";
+    fn test_find_iter_non_overlapping() {
+        let value = "aaa ba";
 
-        let regex = Regex::new("ab.*c.*f").unwrap();
+        let regex = Regex::new("a").unwrap();
+        assert_eq!(regex.find_iter(value).count(), 4);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = Regex::new("aa").unwrap();
+        let matches: Vec<RegexMatch> = regex.find_iter(value).collect();
+        assert_eq!(matches, vec![RegexMatch { start: 0, end: 2 }]);
 
-        Ok(())
+        let regex = Regex::new("z").unwrap();
+        assert_eq!(regex.find_iter(value).count(), 0);
     }
 
     #[test]
-    fn test_no_match_2_point_and_asterisk() -> Result<(), &'static str> {
-        let value = "ab1234cdegh";
+    fn test_exact_repetition_on_class() -> Result<(), &'static str> {
+        let regex = Regex::new("[[:digit:]]{3}").unwrap();
 
-        let regex = Regex::new("ab.*c.*f").unwrap();
+        assert_eq!(
+            regex.find("ab123cd").map(|m| (m.start, m.end)),
+            Some((2, 5))
+        );
+        assert!(!regex.evaluate("ab12cd")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+        let regex = Regex::new("b[[:digit:]]{3}c").unwrap();
+        assert!(regex.evaluate("ab123cd")?.result);
+        assert!(!regex.evaluate("ab1234cd")?.result);
+        assert!(!regex.evaluate("ab12cd")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_literal_and_asterisk() -> Result<(), &'static str> {
-        let value = "ab111cde";
+    fn test_range_repetition_on_bracket() -> Result<(), &'static str> {
+        let regex = Regex::new("[abc]{2,4}").unwrap();
 
-        let regex = Regex::new("ab1*").unwrap();
+        assert_eq!(regex.find("aabbc").unwrap().as_str("aabbc"), "aabb");
+        assert_eq!(regex.find("xab").unwrap().as_str("xab"), "ab");
+        assert!(!regex.evaluate("axbxc")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = Regex::new("^[abc]{2,4}c$").unwrap();
+        assert!(regex.evaluate("aabbc")?.result);
+        assert!(regex.evaluate("abc")?.result);
+        assert!(!regex.evaluate("ac")?.result);
+        assert!(!regex.evaluate("aabbcc")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_no_match_literal_and_asterisk() -> Result<(), &'static str> {
-        let value = "ab111cde";
-
-        let regex = Regex::new("ab2*").unwrap();
+    fn test_stacked_repetitions() {
+        let error = RegexError::InvalidRepetition.message();
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert_eq!(Regex::new("a**").unwrap_err(), error);
+        assert_eq!(Regex::new("a*?").unwrap_err(), error);
+        assert_eq!(Regex::new("a+{2}").unwrap_err(), error);
+        assert_eq!(Regex::new("a{2}*").unwrap_err(), error);
+        assert_eq!(Regex::new("**").unwrap_err(), error);
 
-        Ok(())
+        assert!(Regex::new("a*b+").is_ok());
+        assert!(Regex::new("a\\**").is_ok());
     }
 
     #[test]
-    fn test_no_match_multiple_literal_and_asterisk() -> Result<(), &'static str> {
-        let value = "ab111cde";
+    fn test_closing_brace_literal() -> Result<(), &'static str> {
+        let regex = Regex::new("a}b").unwrap();
+        assert!(regex.evaluate("xa}by")?.result);
+        assert!(!regex.evaluate("ab")?.result);
 
-        let regex = Regex::new("ab2*g*3*").unwrap();
+        let regex = Regex::new("a{2}}").unwrap();
+        assert!(regex.evaluate("aa}")?.result);
+        assert!(!regex.evaluate("a}")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = Regex::new("a\\{2}").unwrap();
+        assert!(regex.evaluate("a{2}")?.result);
+        assert!(!regex.evaluate("aa")?.result);
+
+        let error = Regex::new("a{2").unwrap_err();
+        assert_eq!(error, RegexError::InvalidRange.message());
 
         Ok(())
     }
 
     #[test]
-    fn test_match_single_asterisk() -> Result<(), &'static str> {
-        let value = "abcdefghij";
-
-        let regex = Regex::new("*").unwrap();
+    fn test_builder_default_options() -> Result<(), &'static str> {
+        let regex = RegexBuilder::new("abc").build()?;
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert!(regex.evaluate("xabcx")?.result);
+        assert!(!regex.evaluate("ABC")?.result);
+        assert!(regex.evaluate("añbc").is_err());
 
         Ok(())
     }
 
     #[test]
-    fn test_match_single_point_and_asterisk() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_builder_case_insensitive() -> Result<(), &'static str> {
+        let regex = RegexBuilder::new("a[bc]d").case_insensitive(true).build()?;
 
-        let regex = Regex::new(".*").unwrap();
+        assert!(regex.options.case_insensitive);
+        assert!(regex.evaluate("ABD")?.result);
+        assert!(regex.evaluate("aCd")?.result);
+        assert!(!regex.evaluate("aed")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = RegexBuilder::new("a[^b]c").case_insensitive(true).build()?;
+        assert!(!regex.evaluate("aBc")?.result);
+        assert!(regex.evaluate("aXc")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_point_and_asterisk_at_start() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_builder_unicode() -> Result<(), &'static str> {
+        let regex = RegexBuilder::new("a.c").unicode(true).build()?;
 
-        let regex = Regex::new(".*abcd").unwrap();
+        assert!(regex.options.unicode);
+        assert!(regex.evaluate("añc")?.result);
+        assert!(!regex.evaluate("añbc")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = RegexBuilder::new("ÑU")
+            .case_insensitive(true)
+            .unicode(true)
+            .build()?;
+        assert!(regex.evaluate("el ñu")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_single_asterisk_and_literal() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_builder_invalid_pattern() {
+        let error = RegexBuilder::new("a[bc").build().unwrap_err();
+        assert_eq!(error, RegexError::InvalidBracket.message());
+    }
 
-        let regex = Regex::new(".*fgh").unwrap();
+    #[test]
+    fn test_anchoring_with_point_and_asterisk() -> Result<(), &'static str> {
+        let any_line = Regex::new("^.*$").unwrap();
+        assert!(any_line.evaluate("")?.result);
+        assert!(any_line.evaluate("   ")?.result);
+        assert!(any_line.evaluate("abc")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let any = Regex::new(".*").unwrap();
+        assert!(any.evaluate("")?.result);
+        assert!(any.evaluate(" ")?.result);
+        assert!(any.evaluate("abc")?.result);
+
+        let start_any = Regex::new("^.*").unwrap();
+        assert!(start_any.evaluate("")?.result);
+        assert!(start_any.evaluate("abc")?.result);
+
+        let any_end = Regex::new(".*$").unwrap();
+        assert!(any_end.evaluate("")?.result);
+        assert!(any_end.evaluate("abc")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_question_mark() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_point_and_asterisk_around_literals() -> Result<(), &'static str> {
+        let contains = Regex::new(".*abc.*").unwrap();
+        assert!(contains.evaluate("abc")?.result);
+        assert!(contains.evaluate("xxabcyy")?.result);
+        assert!(!contains.evaluate("ab c")?.result);
+        assert!(!contains.evaluate("")?.result);
+
+        let anchored_contains = Regex::new("^.*abc.*$").unwrap();
+        assert!(anchored_contains.evaluate("abc")?.result);
+        assert!(anchored_contains.evaluate("xxabcyy")?.result);
+        assert!(!anchored_contains.evaluate("xxabyy")?.result);
 
-        let regex = Regex::new("abcd?").unwrap();
+        Ok(())
+    }
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+    #[test]
+    fn test_anchoring_blank_line() -> Result<(), &'static str> {
+        let blank = Regex::new("^$").unwrap();
+
+        assert!(blank.evaluate("")?.result);
+        assert!(!blank.evaluate(" ")?.result);
+        assert!(!blank.evaluate("abc")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_no_match_question_mark() -> Result<(), &'static str> {
-        let value = "abcdefghij";
-
-        let regex = Regex::new("abcr?").unwrap();
+    fn test_split() -> Result<(), &'static str> {
+        let regex = Regex::new("[[:digit:]]+").unwrap();
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert_eq!(regex.split("a1b22c")?, vec!["a", "b", "c"]);
+        assert_eq!(regex.split("abc")?, vec!["abc"]);
+        assert_eq!(regex.split("1a2")?, vec!["", "a", ""]);
+        assert_eq!(regex.split("")?, vec![""]);
+        assert!(regex.split("a1ñ").is_err());
 
         Ok(())
     }
 
     #[test]
-    fn test_match_question_mark_and_point() -> Result<(), &'static str> {
-        let value = "abd";
+    fn test_builder_multiline() -> Result<(), &'static str> {
+        let value = "ab\ncd\nef";
 
-        let regex = Regex::new("ab.?d").unwrap();
-        println!("{:?}", regex);
+        let regex = RegexBuilder::new("b[[:space:]]c").multiline(true).build()?;
+        assert_eq!(regex.find(value), Some(RegexMatch { start: 1, end: 4 }));
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = RegexBuilder::new("a.*d").multiline(true).build()?;
+        assert_eq!(regex.find(value), None);
+
+        let regex = RegexBuilder::new("^cd$").multiline(true).build()?;
+        assert_eq!(regex.find(value), Some(RegexMatch { start: 3, end: 5 }));
+
+        let regex = Regex::new("^cd$").unwrap();
+        assert_eq!(regex.find(value), None);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_question_mark_and_literal() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_builder_dotall() -> Result<(), &'static str> {
+        let value = "a\nb";
 
-        let regex = Regex::new("abc?de.g.*").unwrap();
+        let regex = RegexBuilder::new("a.b").multiline(true).build()?;
+        assert_eq!(regex.find(value), None);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = RegexBuilder::new("a.b")
+            .multiline(true)
+            .dotall(true)
+            .build()?;
+        assert_eq!(regex.find(value), Some(RegexMatch { start: 0, end: 3 }));
 
         Ok(())
     }
 
     #[test]
-    fn test_match_single_plus() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_anchoring_exact_line() -> Result<(), &'static str> {
+        let regex = Regex::new("^abc$").unwrap();
 
-        let regex = Regex::new("abcd+").unwrap();
+        assert!(regex.evaluate("abc")?.result);
+        assert!(!regex.evaluate("abcd")?.result);
+        assert!(!regex.evaluate("xabc")?.result);
+        assert!(!regex.evaluate("abc abc")?.result);
+        assert!(!regex.evaluate("")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = Regex::new("^$").unwrap();
+        assert!(regex.evaluate("")?.result);
+        assert!(!regex.evaluate("abc")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_no_match_plus() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_anchoring_start_mid_pattern_is_literal() -> Result<(), &'static str> {
+        let regex = Regex::new("ab^cd").unwrap();
+        assert!(!regex.steps.iter().any(|step| step.anchoring_start));
+        assert!(regex.evaluate("xab^cdx")?.result);
+        assert!(!regex.evaluate("abcd")?.result);
 
-        let regex = Regex::new("abce+").unwrap();
+        let regex = Regex::new("^ab").unwrap();
+        assert!(regex.steps[0].anchoring_start);
+        assert!(regex.evaluate("abc")?.result);
+        assert!(!regex.evaluate("cab")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+        let regex = Regex::new("x(^ab)").unwrap();
+        assert!(!regex.evaluate("xab")?.result);
+        assert!(Regex::new("(^ab)")?.evaluate("abc")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_multiple_literal_plus() -> Result<(), &'static str> {
-        let value = "abcddddddddef";
+    fn test_anchoring_end_mid_pattern_is_literal() -> Result<(), &'static str> {
+        let regex = Regex::new("a$b").unwrap();
+        assert!(!regex.steps.iter().any(|step| step.anchoring_end));
+        assert!(regex.evaluate("xa$bx")?.result);
+        assert!(!regex.evaluate("ab")?.result);
 
-        let regex = Regex::new("abcd+").unwrap();
+        let regex = Regex::new("ab$").unwrap();
+        assert!(regex.steps[2].anchoring_end);
+        assert!(regex.evaluate("cab")?.result);
+        assert!(!regex.evaluate("abc")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = Regex::new("(ab$)x").unwrap();
+        assert!(!regex.evaluate("abx")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_question_mark_literal_and_plus() -> Result<(), &'static str> {
-        let value = "abcdefghijklllllllm";
+    fn test_new_anchored() -> Result<(), &'static str> {
+        let regex = Regex::new_anchored("abc").unwrap();
+        assert!(regex.evaluate("abc")?.result);
+        assert!(!regex.evaluate("abcd")?.result);
+        assert!(!regex.evaluate("xabc")?.result);
+        assert!(!regex.evaluate("")?.result);
 
-        let regex = Regex::new("abc?de.g.*l+").unwrap();
+        let regex = Regex::new_anchored("a.*").unwrap();
+        assert!(regex.evaluate("abcd")?.result);
+        assert!(!regex.evaluate("babcd")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert!(Regex::new_anchored("a{2").is_err());
 
         Ok(())
     }
 
     #[test]
-    fn test_match_middle_repetition() -> Result<(), &'static str> {
-        let value = "abcccccdeeeeeefghij";
+    fn test_anchoring_start_only_tries_line_starts() -> Result<(), &'static str> {
+        // With a small step limit, trying every position of the line would exceed it
+        let regex = RegexBuilder::new("^abc").step_limit(100).build()?;
+        let long_line = format!("abc{}", "x".repeat(100_000));
+        assert!(regex.evaluate(&long_line).unwrap().result);
 
-        let regex = Regex::new("c*de+fg.i?").unwrap();
+        let long_line = format!("{}abc", "x".repeat(100_000));
+        assert!(!regex.evaluate(&long_line).unwrap().result);
+        assert!(regex.find_at("abcabc", 1).is_none());
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = RegexBuilder::new("^abc").multiline(true).build()?;
+        let found = regex.find_at("abc\nxabc\nabc", 1).unwrap();
+        assert_eq!((found.start, found.end), (9, 12));
 
         Ok(())
     }
 
     #[test]
-    fn test_match_only_plus() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_anchoring_steps_in_order() {
+        let regex = Regex::new("^ab$").unwrap();
 
-        let regex = Regex::new("+").unwrap();
+        assert_eq!(regex.steps.len(), 4);
+        assert!(regex.steps[0].anchoring_start);
+        assert!(regex.steps[3].anchoring_end);
+    }
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+    #[test]
+    fn test_anchoring_start_and_repetition() -> Result<(), &'static str> {
+        let regex = Regex::new("^a.*c$").unwrap();
+        assert!(regex.evaluate("abbbc")?.result);
+        assert!(!regex.evaluate("xabbbc")?.result);
+        assert!(!regex.evaluate("abbbcx")?.result);
+
+        let regex = Regex::new("^*esteo").unwrap();
+        assert_eq!(regex.steps.len(), 7);
+        assert!(regex.evaluate("testeo")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_only_point_and_plus() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_shortest_match() {
+        let regex = Regex::new("a+").unwrap();
+        assert_eq!(regex.shortest_match("aaa"), Some(1));
+        assert_eq!(regex.shortest_match("baaa"), None);
+        assert_eq!(regex.shortest_match(""), None);
 
-        let regex = Regex::new(".+").unwrap();
+        let regex = Regex::new("a*").unwrap();
+        assert_eq!(regex.shortest_match("aaa"), Some(0));
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = Regex::new("a.*c").unwrap();
+        assert_eq!(regex.shortest_match("abcbc"), Some(3));
 
-        Ok(())
+        let regex = Regex::new("ab$").unwrap();
+        assert_eq!(regex.shortest_match("abab"), None);
     }
 
     #[test]
-    fn test_match_only_question_mark() -> Result<(), &'static str> {
-        let value = "abcdefghij";
+    fn test_matching_lines() -> Result<(), &'static str> {
+        let text = "abcd\nabecd\nab10cd";
 
-        let regex = Regex::new("?").unwrap();
+        let regex = Regex::new("ab.*cd")?;
+        assert_eq!(regex.matching_lines(text)?, vec!["abcd", "abecd", "ab10cd"]);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = Regex::new("^ab.cd$")?;
+        assert_eq!(regex.matching_lines(text)?, vec!["abecd"]);
+
+        let regex = Regex::new("z")?;
+        assert!(regex.matching_lines(text)?.is_empty());
+        assert!(regex.matching_lines("a\n\u{e9}").is_err());
+
+        let regex = Regex::new("^$")?;
+        assert!(regex.matching_lines("a\n")?.is_empty());
+        assert_eq!(regex.matching_lines("a\n\nb\n")?, vec![""]);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_only_point_and_question_mark() -> Result<(), &'static str> {
-        let value = "abcdefghij";
-
-        let regex = Regex::new(".?").unwrap();
+    fn test_step_limit_exceeded() {
+        let regex = RegexBuilder::new("(a*a*a*a*a*)*b")
+            .step_limit(100)
+            .build()
+            .unwrap();
+        let value = format!("{}cb", "a".repeat(30));
+
+        let error = regex.evaluate(&value).unwrap_err();
+        assert_eq!(error, RegexError::ComplexityLimitExceeded.message());
+        assert!(regex.find(&value).is_none());
+
+        let line = regex.evaluate("aab").unwrap();
+        assert!(line.result);
 
-        let line = regex.evaluate(value)?;
+        let nested = RegexBuilder::new("(a*)*b")
+            .step_limit(10_000)
+            .build()
+            .unwrap();
+        let line = nested.evaluate(&value).unwrap();
         assert!(line.result);
+    }
+
+    #[test]
+    fn test_backtracking_with_required_literals() -> Result<(), &'static str> {
+        let regex = Regex::new("a*a*a*b")?;
+        let value = format!("{}xb", "a".repeat(1000));
+        let found = regex.find(&value).map(|m| (m.start, m.end));
+        assert_eq!(found, Some((value.len() - 1, value.len())));
 
+        let line = Regex::new("a*a*a*ba")?.evaluate(&format!("{}b", "a".repeat(1000)))?;
+        assert!(!line.result);
         Ok(())
     }
 
     #[test]
-    fn test_match_empty_line() -> Result<(), &'static str> {
-        let value = "";
+    fn test_backtracking_with_failing_literals() -> Result<(), &'static str> {
+        let value = format!("{}b", "a".repeat(10_000));
 
-        let regex1 = Regex::new("*").unwrap();
-        let regex2 = Regex::new("+").unwrap();
-        let regex3 = Regex::new("?").unwrap();
+        let line = Regex::new("a*a*a*ba")?.evaluate(&value)?;
+        assert!(!line.result);
 
-        let line1 = regex1.evaluate(value)?;
-        let line2 = regex2.evaluate(value)?;
-        let line3 = regex3.evaluate(value)?;
+        let line = Regex::new("(a*a*a*)ba")?.evaluate(&value)?;
+        assert!(!line.result);
 
-        assert!(line1.result);
-        assert!(line2.result);
-        assert!(line3.result);
+        let line = Regex::new("(a|aa)*ba")?.evaluate(&value)?;
+        assert!(!line.result);
 
+        let regex = Regex::new("a*a*a*b")?;
+        let value = format!("{}xb", "a".repeat(10_000));
+        let found = regex.find(&value).map(|m| (m.start, m.end));
+        assert_eq!(found, Some((value.len() - 1, value.len())));
         Ok(())
     }
 
     #[test]
-    fn test_match_start_with_repetition() -> Result<(), &'static str> {
-        let value = "testeo";
+    fn test_backtracking_with_alternatives() -> Result<(), &'static str> {
+        let value = format!("{}b", "a".repeat(10_000));
 
-        let regex1 = Regex::new("*esteo").unwrap();
-        let regex2 = Regex::new("+esteo").unwrap();
-        let regex3 = Regex::new("?esteo").unwrap();
+        let line = Regex::new("x|a*a*a*ba")?.evaluate(&value)?;
+        assert!(!line.result);
 
-        let line1 = regex1.evaluate(value)?;
-        let line2 = regex2.evaluate(value)?;
-        let line3 = regex3.evaluate(value)?;
+        let line = Regex::new("(a*)*bx|c")?.evaluate(&value)?;
+        assert!(!line.result);
 
-        assert!(line1.result);
-        assert!(!line2.result);
-        assert!(line3.result);
+        let line = Regex::new("x|(a*a*|b)*ba")?.evaluate(&value)?;
+        assert!(!line.result);
 
+        let found = Regex::new("x|a*a*a*b")?
+            .find(&value)
+            .map(|m| (m.start, m.end));
+        assert_eq!(found, Some((0, value.len())));
+
+        let line = Regex::new("(a*)*b|c")?.evaluate(&format!("{}c", "a".repeat(10_000)))?;
+        assert!(line.result);
         Ok(())
     }
 
     #[test]
-    fn test_match_range_combination_with_start_and_end() -> Result<(), &'static str> {
-        let value = "abccccc";
+    fn test_long_group_repetition() -> Result<(), &'static str> {
+        let value = "a".repeat(100_000);
 
-        let regex = Regex::new("abc{2,10}").unwrap();
+        let line = Regex::new("(a)+$")?.evaluate(&value)?;
+        assert!(line.result);
+        assert_eq!(line.matched_len, value.len());
 
-        let line = regex.evaluate(value)?;
+        let regex = Regex::new("(a)*")?;
+        let found = regex.find(&value).map(|m| (m.start, m.end));
+        assert_eq!(found, Some((0, value.len())));
+        let captures = regex.captures(&value).unwrap();
+        assert_eq!(captures[1].as_deref(), Some("a"));
+
+        let line = Regex::new("(ab|a)+c")?.evaluate(&format!("{value}c"))?;
         assert!(line.result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_source() -> Result<(), &'static str> {
+        assert_eq!(Regex::new("a.c")?.pattern(), "a.c");
+        assert_eq!(Regex::new_anchored("[a-c]+")?.pattern(), "[a-c]+");
+        assert_eq!(RegexBuilder::new("x|y").build()?.pattern(), "x|y");
 
         Ok(())
     }
 
     #[test]
-    fn test_no_match_range_combination_with_start_and_end() -> Result<(), &'static str> {
-        let value = "abc";
+    fn test_evaluate_bytes() -> Result<(), &'static str> {
+        let regex = Regex::new("a.b")?;
+        assert!(regex.evaluate_bytes(b"a\xFFb")?);
+        assert!(!regex.evaluate_bytes(b"a\xFF\xFEb")?);
 
-        let regex = Regex::new("abc{2,10}").unwrap();
+        let regex = Regex::new("^[^a]ab$")?;
+        assert!(regex.evaluate_bytes(b"\xFFab")?);
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+        let regex = Regex::new("[[:alpha:]]")?;
+        assert!(!regex.evaluate_bytes(b"\xFF\x80 1")?);
+        assert!(regex.evaluate_bytes(b"\xFFz")?);
 
         Ok(())
     }
 
     #[test]
-    fn test_match_range_combination_exact() -> Result<(), &'static str> {
-        let value1 = "abccccc33";
-        let value2 = "aaa";
+    fn test_captures_groups() {
+        let regex = Regex::new("(a.)(c.)").unwrap();
 
-        let regex1 = Regex::new("abc{5}").unwrap();
-        let regex2 = Regex::new("a{3}").unwrap();
+        let captures = regex.captures("axcy").unwrap();
+        assert_eq!(
+            captures,
+            vec![
+                Some("axcy".to_string()),
+                Some("ax".to_string()),
+                Some("cy".to_string())
+            ]
+        );
+        assert!(regex.captures("axby").is_none());
+    }
 
-        let line = regex1.evaluate(value1)?;
-        assert!(line.result);
-        let line = regex2.evaluate(value2)?;
-        assert!(line.result);
+    #[test]
+    fn test_captures_non_participating_group() {
+        let regex = Regex::new("(a)?b").unwrap();
 
-        Ok(())
+        let captures = regex.captures("xb").unwrap();
+        assert_eq!(captures, vec![Some("b".to_string()), None]);
     }
 
     #[test]
-    fn test_no_match_range_combination_exact() -> Result<(), &'static str> {
-        let value = "abcc33";
-
-        let regex = Regex::new("abc{5}").unwrap();
+    fn test_captures_repeated_group() -> Result<(), &'static str> {
+        let regex = Regex::new("(ab)+c").unwrap();
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+        let captures = regex.captures("xababc").unwrap();
+        assert_eq!(
+            captures,
+            vec![Some("ababc".to_string()), Some("ab".to_string())]
+        );
+        assert!(regex.evaluate("abc")?.result);
+        assert!(!regex.evaluate("ac")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_no_match_range_combination_exact_2() -> Result<(), &'static str> {
-        let value = "abcccccc33";
+    fn test_captures_nested_groups() {
+        let regex = Regex::new("((a*)b)").unwrap();
 
-        let regex = Regex::new("abc{5}3").unwrap();
+        let captures = regex.captures("xaab").unwrap();
+        assert_eq!(
+            captures,
+            vec![
+                Some("aab".to_string()),
+                Some("aab".to_string()),
+                Some("aa".to_string())
+            ]
+        );
+    }
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+    #[test]
+    fn test_unbalanced_groups() {
+        let error = RegexError::InvalidGroup.message();
 
-        Ok(())
+        assert_eq!(Regex::new("(ab").unwrap_err(), error);
+        assert_eq!(Regex::new("ab)").unwrap_err(), error);
     }
 
     #[test]
-    fn test_match_range_combination_only_start() -> Result<(), &'static str> {
-        let value = "abccccc";
+    fn test_replace_all_group_references() -> Result<(), &'static str> {
+        let regex = Regex::new("(a)(b)").unwrap();
+
+        assert_eq!(regex.replace_all("abab", "$2$1")?, "baba");
+        assert_eq!(regex.replace_all("xaby", "<$0>")?, "x<ab>y");
+        assert_eq!(regex.replace_all("ab", "$$1")?, "$1");
+
+        Ok(())
+    }
 
-        let regex = Regex::new("abc{2,}").unwrap();
+    #[test]
+    fn test_replace_all_missing_groups() -> Result<(), &'static str> {
+        let regex = Regex::new("(x)?b").unwrap();
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        assert_eq!(regex.replace_all("abc", "[$1$7]")?, "a[]c");
+        assert_eq!(regex.replace_all("abc", "$")?, "a$c");
 
         Ok(())
     }
 
     #[test]
-    fn test_no_match_range_combination_only_start() -> Result<(), &'static str> {
-        let value = "abc";
-
-        let regex = Regex::new("abc{2,}").unwrap();
+    fn test_replace_all_empty_matches() -> Result<(), &'static str> {
+        let regex = Regex::new("x*").unwrap();
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+        assert_eq!(regex.replace_all("ab", "-")?, "-a-b-");
 
         Ok(())
     }
 
     #[test]
-    fn test_match_range_combination_only_end() -> Result<(), &'static str> {
-        let value = "abcccd";
-
-        let regex = Regex::new("abc{,5}").unwrap();
+    fn test_new_literal() -> Result<(), &'static str> {
+        let regex = Regex::new_literal("a.b*c");
+        assert!(regex.evaluate("xa.b*cx")?.result);
+        assert!(!regex.evaluate("axbbc")?.result);
+        assert!(!regex.evaluate("ac")?.result);
 
-        let line = regex.evaluate(value)?;
-        assert!(line.result);
+        let regex = Regex::new_literal("^a$");
+        assert!(regex.evaluate("x^a$x")?.result);
+        assert!(!regex.evaluate("a")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_no_match_range_combination_only_end() -> Result<(), &'static str> {
-        let value = "abccccccd";
-
-        let regex = Regex::new("abc{,5}d").unwrap();
+    fn test_escape() {
+        let escaped = escape("a.c[d](e)|^$\\");
+        let regex = Regex::new(&escaped).unwrap();
+        assert!(regex.evaluate("a.c[d](e)|^$\\").unwrap().result);
+        assert!(!regex.evaluate("axc[d](e)|^$\\").unwrap().result);
+    }
 
-        let line = regex.evaluate(value)?;
-        assert!(!line.result);
+    #[test]
+    fn test_find_overlapping() -> Result<(), &'static str> {
+        let regex = Regex::new("aa")?;
+        let found: Vec<(usize, usize)> = regex
+            .find_overlapping("aaaa")
+            .map(|m| (m.start, m.end))
+            .collect();
+        assert_eq!(found, vec![(0, 2), (1, 3), (2, 4)]);
+        assert_eq!(regex.find_iter("aaaa").count(), 2);
+
+        let regex = Regex::new("a.a")?;
+        let starts: Vec<usize> = regex.find_overlapping("xaéaba").map(|m| m.start).collect();
+        assert_eq!(starts, vec![1, 4]);
 
         Ok(())
     }
 
     #[test]
-    fn test_backslash_basic() -> Result<(), &'static str> {
-        let value1 = "bca.bc";
-        let regex1 = Regex::new("a\\.").unwrap();
-        let line1 = regex1.evaluate(value1)?;
-        assert!(line1.result);
-
-        let value2 = "bcabc";
-        let regex2 = Regex::new("a\\.").unwrap();
-        let line2 = regex2.evaluate(value2)?;
-        assert!(!line2.result);
+    fn test_find_overlapping_empty_matches() -> Result<(), &'static str> {
+        let regex = Regex::new("b*")?;
+        let found: Vec<(usize, usize)> = regex
+            .find_overlapping("abb")
+            .map(|m| (m.start, m.end))
+            .collect();
+        assert_eq!(found, vec![(0, 0), (1, 3), (2, 3), (3, 3)]);
 
-        let value3 = "{abc";
-        let regex3 = Regex::new("\\{abc").unwrap();
-        let line3 = regex3.evaluate(value3)?;
-        assert!(line3.result);
+        Ok(())
+    }
 
-        let value4 = "abc";
-        let regex4 = Regex::new("\\abc").unwrap();
-        let line4 = regex4.evaluate(value4)?;
-        assert!(line4.result);
+    #[test]
+    fn test_evaluate_matched_len() -> Result<(), &'static str> {
+        let line = Regex::new("ab.*e")?.evaluate("abcde")?;
+        assert!(line.result);
+        assert_eq!(line.matched_len, 5);
 
-        let value5 = ".e+e?e";
-        let regex5 = Regex::new("\\.e\\+e\\?e").unwrap();
-        let line5 = regex5.evaluate(value5)?;
-        assert!(line5.result);
+        let line = Regex::new("c.*e")?.evaluate("abcdefe!")?;
+        assert_eq!(line.matched_len, 5);
 
-        let regex6 = Regex::new("abc\\").unwrap_err();
-        assert_eq!(regex6, "Invalid regex: invalid backslash");
+        let line = Regex::new("x")?.evaluate("abcde")?;
+        assert!(!line.result);
+        assert_eq!(line.matched_len, 0);
 
         Ok(())
     }
 
     #[test]
-    fn test_backslash_backslash() -> Result<(), &'static str> {
-        let value1 = "bca\\bc";
-        let regex1 = Regex::new("a\\\\b").unwrap();
-        let line1 = regex1.evaluate(value1)?;
-        assert!(line1.result);
+    fn test_alternation_anchors_per_branch() -> Result<(), &'static str> {
+        let regex = Regex::new("^start|end$")?;
 
-        let value2 = "bcabc";
-        let regex2 = Regex::new("a\\\\b").unwrap();
-        let line2 = regex2.evaluate(value2)?;
-        assert!(!line2.result);
+        assert!(regex.evaluate("start middle end")?.result);
+        assert!(regex.evaluate("start with start")?.result);
+        assert!(regex.evaluate("end with end")?.result);
+        assert!(!regex.evaluate("only this line")?.result);
+        assert!(!regex.evaluate("the start")?.result);
+        assert!(!regex.evaluate("end of line")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_anchoring_start() -> Result<(), &'static str> {
-        let value1 = "start middle end";
-        let value2 = "start with start";
-        let value3 = "end with end";
-        let value4 = "only this line";
+    fn test_empty_line_anchors() -> Result<(), &'static str> {
+        let regex = Regex::new("^$")?;
 
-        let regex = Regex::new("^start").unwrap();
+        assert!(regex.is_anchored_start() && regex.is_anchored_end());
+        assert!(regex.evaluate("")?.result);
+        assert!(!regex.evaluate("a")?.result);
+        assert!(!regex.evaluate(" ")?.result);
 
-        let line1 = regex.clone().evaluate(value1)?;
-        let line2 = regex.clone().evaluate(value2)?;
-        let line3 = regex.clone().evaluate(value3)?;
-        let line4 = regex.evaluate(value4)?;
+        let regex = Regex::new("^$|abc")?;
 
-        assert!(line1.result);
-        assert!(line2.result);
-        assert!(!line3.result);
-        assert!(!line4.result);
+        assert!(regex.evaluate("")?.result);
+        assert!(regex.evaluate("xabcx")?.result);
+        assert!(!regex.evaluate("a")?.result);
+        assert!(!regex.evaluate(" ")?.result);
+
+        let regex = RegexBuilder::new("^$").multiline(true).build()?;
+        let found = regex.find("a\n\nb").unwrap();
+        assert_eq!((found.start, found.end), (2, 2));
+        assert!(regex.find("a\nb").is_none());
 
         Ok(())
     }
 
     #[test]
-    fn test_anchoring_end() -> Result<(), &'static str> {
-        let value1 = "start middle end";
-        let value2 = "start with start";
-        let value3 = "end with end";
-        let value4 = "only this line";
+    fn test_lone_anchors() -> Result<(), &'static str> {
+        for pattern in ["^", "$"] {
+            let regex = Regex::new(pattern)?;
 
-        let regex = Regex::new("end$").unwrap();
+            assert!(regex.evaluate("")?.result);
+            assert!(regex.evaluate("abc")?.result);
+            assert!(regex.evaluate(" ")?.result);
+        }
 
-        let line1 = regex.clone().evaluate(value1)?;
-        let line2 = regex.clone().evaluate(value2)?;
-        let line3 = regex.clone().evaluate(value3)?;
-        let line4 = regex.evaluate(value4)?;
+        let regex = Regex::new("^$")?;
 
-        assert!(line1.result);
-        assert!(!line2.result);
-        assert!(line3.result);
-        assert!(!line4.result);
+        assert!(regex.evaluate("")?.result);
+        assert!(!regex.evaluate("abc")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_anchoring_fails() -> Result<(), &'static str> {
-        let value1 = "start middle end";
-        let regex1 = Regex::new("^middle").unwrap();
-        let line1 = regex1.evaluate(value1)?;
-        assert!(!line1.result);
+    fn test_repeated_anchors() -> Result<(), &'static str> {
+        let pairs = [("^^abc", "^abc"), ("abc$$", "abc$"), ("^^abc$$", "^abc$")];
+        let values = ["abc", "abcd", "xabc", "xabcx", ""];
+
+        for (repeated, single) in pairs {
+            let repeated = Regex::new(repeated)?;
+            let single = Regex::new(single)?;
+            assert_eq!(repeated.steps.len(), single.steps.len());
+
+            for value in values {
+                assert_eq!(
+                    repeated.evaluate(value)?.result,
+                    single.evaluate(value)?.result
+                );
+            }
+        }
 
-        let value2 = "start middle end";
-        let regex2 = Regex::new("middle$").unwrap();
-        let line2 = regex2.evaluate(value2)?;
-        assert!(!line2.result);
+        assert!(Regex::new("^^$$")?.evaluate("")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_bracket_expressions() -> Result<(), &'static str> {
-        let value1 = "abc";
-        let value2 = "acc";
-        let value3 = "azc";
-        let value4 = "a9c";
-        let value5 = "a3";
-        let value6 = "ae";
-        let value7 = "aec";
-        let value8 = "aaaaaaeccccccc";
-        let value9 = "aabcdefc";
+    fn test_alternation_inside_group() -> Result<(), &'static str> {
+        let regex = Regex::new("gr(a|e)y")?;
 
-        let regex = Regex::new("a[abcdef]c").unwrap();
+        assert!(regex.evaluate("grey")?.result);
+        assert!(regex.evaluate("gray")?.result);
+        assert!(!regex.evaluate("gry")?.result);
+        assert_eq!(
+            regex.captures("a gray cat"),
+            Some(vec![Some("gray".to_string()), Some("a".to_string())])
+        );
 
-        let line1 = regex.clone().evaluate(value1)?;
-        let line2 = regex.clone().evaluate(value2)?;
-        let line3 = regex.clone().evaluate(value3)?;
-        let line4 = regex.clone().evaluate(value4)?;
-        let line5 = regex.clone().evaluate(value5)?;
-        let line6 = regex.clone().evaluate(value6)?;
-        let line7 = regex.clone().evaluate(value7)?;
-        let line8 = regex.clone().evaluate(value8)?;
-        let line9 = regex.evaluate(value9)?;
+        let regex = Regex::new("(ab|a)bc")?;
+        assert_eq!(regex.find("abc").map(|m| m.end), Some(3));
 
-        assert!(line1.result);
-        assert!(line2.result);
-        assert!(!line3.result);
-        assert!(!line4.result);
-        assert!(!line5.result);
-        assert!(!line6.result);
-        assert!(line7.result);
-        assert!(line8.result);
-        assert!(line9.result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_alternation_first_alternative_wins() -> Result<(), &'static str> {
+        let regex = Regex::new("a|ab")?;
+        assert_eq!(regex.find("xab"), Some(RegexMatch { start: 1, end: 2 }));
+
+        let regex = Regex::new("b|ab")?;
+        assert_eq!(regex.find("xab"), Some(RegexMatch { start: 1, end: 3 }));
 
         Ok(())
     }
 
     #[test]
-    fn test_negated_bracket_expressions() -> Result<(), &'static str> {
-        let value1 = "abc";
-        let value2 = "acc";
-        let value3 = "azc";
-        let value4 = "a9c";
-        let value5 = "a3";
-        let value6 = "ae";
-        let value7 = "aec";
-        let value8 = "aaaaaaeccccccc";
-        let value9 = "ahcalcazcakc";
+    fn test_val_matches_distinguishes_no_match() {
+        let vals = [
+            RegexVal::Literal('a'),
+            RegexVal::Wildcard,
+            RegexVal::Class(RegexClass::Digit),
+            RegexVal::Bracket(CharSet::from(vec!['a', 'b'])),
+        ];
+
+        for val in &vals {
+            assert_eq!(val.matches(""), None);
+        }
+        assert_eq!(vals[0].matches("a"), Some(1));
+        assert_eq!(vals[1].matches("\u{e9}"), Some(2));
+        assert_eq!(vals[2].matches("a1"), None);
+        assert_eq!(vals[3].matches("c"), None);
+
+        let group = RegexVal::Group {
+            index: 1,
+            steps: Vec::new(),
+        };
+        assert_eq!(group.matches("abc"), None);
+
+        // The boundaries are zero width, they are only matched by the regex at a position
+        for val in [RegexVal::WordStart, RegexVal::TextStart, RegexVal::TextEnd] {
+            assert_eq!(val.matches(""), None);
+            assert_eq!(val.matches("ab"), None);
+        }
+    }
 
-        let regex = Regex::new("a[^ghijkl]c").unwrap();
+    #[test]
+    fn test_word_boundaries() -> Result<(), &'static str> {
+        let regex = Regex::new("\\<cat\\>")?;
 
-        let line1 = regex.clone().evaluate(value1)?;
-        let line2 = regex.clone().evaluate(value2)?;
-        let line3 = regex.clone().evaluate(value3)?;
-        let line4 = regex.clone().evaluate(value4)?;
-        let line5 = regex.clone().evaluate(value5)?;
-        let line6 = regex.clone().evaluate(value6)?;
-        let line7 = regex.clone().evaluate(value7)?;
-        let line8 = regex.clone().evaluate(value8)?;
-        let line9 = regex.evaluate(value9)?;
+        assert!(regex.evaluate("a cat b")?.result);
+        assert!(regex.evaluate("cat")?.result);
+        assert!(regex.evaluate("(cat)")?.result);
+        assert!(!regex.evaluate("scatter")?.result);
+        assert!(!regex.evaluate("cats")?.result);
+        assert!(!regex.evaluate("_cat")?.result);
 
-        assert!(line1.result);
-        assert!(line2.result);
-        assert!(line3.result);
-        assert!(line4.result);
-        assert!(!line5.result);
-        assert!(!line6.result);
-        assert!(line7.result);
-        assert!(line8.result);
-        assert!(line9.result);
+        let regex = Regex::new("\\<cat")?;
+
+        assert!(regex.evaluate("cat")?.result);
+        assert!(regex.evaluate("the catalog")?.result);
+        assert!(!regex.evaluate("scat")?.result);
+
+        let regex = Regex::new("\\>")?;
+        let found = regex.find("ab cd").unwrap();
+        assert_eq!((found.start, found.end), (2, 2));
+        assert!(regex.find("  ").is_none());
 
         Ok(())
     }
 
-    const VALUE1: &str = "abc";
-    const VALUE2: &str = "a1c";
-    const VALUE3: &str = "a%c";
-    const VALUE4: &str = "aBc";
-    const VALUE5: &str = "a c";
-    const VALUE6: &str = "a-c";
+    #[test]
+    fn test_literal_leading_quantifiers() -> Result<(), &'static str> {
+        let literal = |pattern| {
+            RegexBuilder::new(pattern)
+                .literal_leading_quantifiers(true)
+                .build()
+        };
+
+        let regex = literal("*abc")?;
+        assert!(regex.evaluate("*abc")?.result);
+        assert!(regex.evaluate("x*abcx")?.result);
+        assert!(!regex.evaluate("abc")?.result);
+
+        let regex = literal("^*a|(+b)|?c")?;
+        assert!(regex.evaluate("*a")?.result);
+        assert!(!regex.evaluate("x*a")?.result);
+        assert!(regex.evaluate("+b")?.result);
+        assert!(!regex.evaluate("b")?.result);
+        assert!(regex.evaluate("?c")?.result);
+        assert!(!regex.evaluate("c")?.result);
+
+        let regex = literal("*+x")?;
+        assert!(regex.evaluate("***x")?.result);
+        assert!(!regex.evaluate("x")?.result);
+
+        let regex = literal("*")?;
+        assert!(regex.evaluate("a*")?.result);
+        assert!(!regex.evaluate("abc")?.result);
+
+        let regex = literal("ab*c")?;
+        assert!(regex.evaluate("ac")?.result);
+        assert!(regex.evaluate("abbc")?.result);
+
+        assert!(Regex::new("*abc")?.evaluate("abc")?.result);
+        assert!(Regex::new("*")?.evaluate("abc")?.result);
+
+        Ok(())
+    }
 
     #[test]
-    fn test_regex_alnum_class() -> Result<(), &'static str> {
-        // Alphanumeric
-        let alnum_regex = Regex::new("a[[:alnum:]]c").unwrap();
+    fn test_empty_pattern() -> Result<(), &'static str> {
+        for pattern in ["", "|", "||"] {
+            assert_eq!(
+                Regex::new(pattern).unwrap_err(),
+                RegexError::EmptyPattern.message()
+            );
+        }
+        assert_eq!(
+            RegexBuilder::new("").multiline(true).build().unwrap_err(),
+            RegexError::EmptyPattern.message()
+        );
 
-        let alnum_line1 = alnum_regex.clone().evaluate(VALUE1)?;
-        let alnum_line2 = alnum_regex.clone().evaluate(VALUE2)?;
-        let alnum_line3 = alnum_regex.clone().evaluate(VALUE3)?;
-        let alnum_line4 = alnum_regex.clone().evaluate(VALUE4)?;
-        let alnum_line5 = alnum_regex.clone().evaluate(VALUE5)?;
-        let alnum_line6 = alnum_regex.evaluate(VALUE6)?;
+        let regex = Regex::new("|a|")?;
+        assert!(regex.evaluate("a")?.result);
+        assert!(!regex.evaluate("")?.result);
+        assert!(!regex.evaluate("b")?.result);
 
-        assert!(alnum_line1.result);
-        assert!(alnum_line2.result);
-        assert!(!alnum_line3.result);
-        assert!(alnum_line4.result);
-        assert!(!alnum_line5.result);
-        assert!(!alnum_line6.result);
+        for pattern in ["()", "^", "$", "a{0}"] {
+            assert!(Regex::new(pattern)?.evaluate("")?.result);
+        }
 
         Ok(())
     }
 
     #[test]
-    fn test_regex_alpha_class() -> Result<(), &'static str> {
-        // Alphabetic
-        let alpha_regex = Regex::new("a[[:alpha:]]c").unwrap();
+    fn test_step_views() -> Result<(), &'static str> {
+        assert_eq!(Regex::new("a.c")?.step_count(), 3);
 
-        let alpha_line1 = alpha_regex.clone().evaluate(VALUE1)?;
-        let alpha_line2 = alpha_regex.clone().evaluate(VALUE2)?;
-        let alpha_line3 = alpha_regex.clone().evaluate(VALUE3)?;
-        let alpha_line4 = alpha_regex.clone().evaluate(VALUE4)?;
-        let alpha_line5 = alpha_regex.clone().evaluate(VALUE5)?;
-        let alpha_line6 = alpha_regex.evaluate(VALUE6)?;
+        let regex = Regex::new("^a+[bc]\\w(d|e)$")?;
+        let views: Vec<(StepKind, (usize, usize))> = regex
+            .step_views()
+            .map(|step| (step.kind(), step.rep().bounds()))
+            .collect();
 
-        assert!(alpha_line1.result);
-        assert!(!alpha_line2.result);
-        assert!(!alpha_line3.result);
-        assert!(alpha_line4.result);
-        assert!(!alpha_line5.result);
-        assert!(!alpha_line6.result);
+        assert_eq!(regex.step_count(), views.len());
+        assert_eq!(
+            views,
+            vec![
+                (StepKind::LineStart, (0, usize::MAX)),
+                (StepKind::Literal('a'), (1, usize::MAX)),
+                (StepKind::Bracket, (1, 1)),
+                (StepKind::Class, (1, 1)),
+                (StepKind::Group, (1, 1)),
+                (StepKind::LineEnd, (0, usize::MAX)),
+            ]
+        );
 
         Ok(())
     }
 
     #[test]
-    fn test_regex_digit_class() -> Result<(), &'static str> {
-        // Digit - Numeric
-        let digit_regex = Regex::new("a[[:digit:]]c").unwrap();
+    fn test_absolute_anchors() -> Result<(), &'static str> {
+        let regex = RegexBuilder::new("\\Aab").multiline(true).build()?;
+        let starts: Vec<usize> = regex
+            .find_iter("ab\nab\nab")
+            .map(|found| found.start)
+            .collect();
+        assert_eq!(starts, vec![0]);
+
+        let regex = RegexBuilder::new("^ab").multiline(true).build()?;
+        assert_eq!(regex.find_iter("ab\nab\nab").count(), 3);
+
+        let regex = RegexBuilder::new("ab\\z").multiline(true).build()?;
+        let starts: Vec<usize> = regex
+            .find_iter("ab\nab\nab")
+            .map(|found| found.start)
+            .collect();
+        assert_eq!(starts, vec![6]);
+        assert!(regex.find("ab\n").is_none());
+
+        let regex = Regex::new("\\Aab\\z")?;
+        assert!(regex.evaluate("ab")?.result);
+        assert!(!regex.evaluate("xab")?.result);
+        assert!(!regex.evaluate("abx")?.result);
 
-        let digit_line1 = digit_regex.clone().evaluate(VALUE1)?;
-        let digit_line2 = digit_regex.clone().evaluate(VALUE2)?;
-        let digit_line3 = digit_regex.clone().evaluate(VALUE3)?;
-        let digit_line4 = digit_regex.clone().evaluate(VALUE4)?;
-        let digit_line5 = digit_regex.clone().evaluate(VALUE5)?;
-        let digit_line6 = digit_regex.evaluate(VALUE6)?;
+        Ok(())
+    }
 
-        assert!(!digit_line1.result);
-        assert!(digit_line2.result);
-        assert!(!digit_line3.result);
-        assert!(!digit_line4.result);
-        assert!(!digit_line5.result);
-        assert!(!digit_line6.result);
+    #[test]
+    fn test_quantifiers_attach_to_whole_construct() -> Result<(), &'static str> {
+        let cases = [
+            ("x[abc]*y", "xabcay", (0, 6)),
+            ("x[[:digit:]]+y", "ax123y", (1, 6)),
+            ("x\\w?y", "xzy", (0, 3)),
+            ("x(ab)*y", "xababy", (0, 6)),
+            ("x(a|bc)+y", "xabcay", (0, 6)),
+        ];
+
+        for (pattern, value, expected) in cases {
+            let regex = Regex::new(pattern)?;
+            assert_eq!(regex.steps.len(), 3);
+            assert!(matches!(
+                regex.steps[1].rep,
+                RegexRep::Any | RegexRep::Range { .. }
+            ));
+
+            let found = regex.find(value).unwrap();
+            assert_eq!((found.start, found.end), expected);
+        }
+
+        assert!(Regex::new("x[[:digit:]]+y")?.find("xy").is_none());
+        assert!(Regex::new("x(ab)*y")?.find("xaby").is_some());
+        assert!(Regex::new("x(ab)*y")?.find("xay").is_none());
 
         Ok(())
     }
 
     #[test]
-    fn test_regex_lower_class() -> Result<(), &'static str> {
-        // Lowercase letters
-        let lower_regex = Regex::new("a[[:lower:]]c").unwrap();
+    fn test_word_class() -> Result<(), &'static str> {
+        let regex = Regex::new("^[[:word:]]$")?;
+        assert!(regex.evaluate("a")?.result);
+        assert!(regex.evaluate("1")?.result);
+        assert!(regex.evaluate("_")?.result);
+        assert!(!regex.evaluate("-")?.result);
 
-        let lower_line1 = lower_regex.clone().evaluate(VALUE1)?;
-        let lower_line2 = lower_regex.clone().evaluate(VALUE2)?;
-        let lower_line3 = lower_regex.clone().evaluate(VALUE3)?;
-        let lower_line4 = lower_regex.clone().evaluate(VALUE4)?;
-        let lower_line5 = lower_regex.clone().evaluate(VALUE5)?;
-        let lower_line6 = lower_regex.evaluate(VALUE6)?;
+        Ok(())
+    }
 
-        assert!(lower_line1.result);
-        assert!(!lower_line2.result);
-        assert!(!lower_line3.result);
-        assert!(!lower_line4.result);
-        assert!(!lower_line5.result);
-        assert!(!lower_line6.result);
+    #[test]
+    fn test_word_shorthand() -> Result<(), &'static str> {
+        let regex = Regex::new("\\w+")?;
+        let found = regex.find("-- foo_bar1 --").unwrap();
+        assert_eq!(found.as_str("-- foo_bar1 --"), "foo_bar1");
+
+        let regex = Regex::new("a\\Wb")?;
+        assert!(regex.evaluate("a-b")?.result);
+        assert!(regex.evaluate("a b")?.result);
+        assert!(!regex.evaluate("a_b")?.result);
 
         Ok(())
     }
 
     #[test]
-    fn test_regex_upper_class() -> Result<(), &'static str> {
-        // Uppercase letters
-        let upper_regex = Regex::new("a[[:upper:]]c").unwrap();
+    fn test_is_anchored() -> Result<(), &'static str> {
+        let regex = Regex::new("^abc")?;
+        assert!(regex.is_anchored_start());
+        assert!(!regex.is_anchored_end());
 
-        let upper_line1 = upper_regex.clone().evaluate(VALUE1)?;
-        let upper_line2 = upper_regex.clone().evaluate(VALUE2)?;
-        let upper_line3 = upper_regex.clone().evaluate(VALUE3)?;
-        let upper_line4 = upper_regex.clone().evaluate(VALUE4)?;
-        let upper_line5 = upper_regex.clone().evaluate(VALUE5)?;
-        let upper_line6 = upper_regex.evaluate(VALUE6)?;
+        let regex = Regex::new("abc$")?;
+        assert!(!regex.is_anchored_start());
+        assert!(regex.is_anchored_end());
 
-        assert!(!upper_line1.result);
-        assert!(!upper_line2.result);
-        assert!(!upper_line3.result);
-        assert!(upper_line4.result);
-        assert!(!upper_line5.result);
-        assert!(!upper_line6.result);
+        let regex = Regex::new("abc")?;
+        assert!(!regex.is_anchored_start());
+        assert!(!regex.is_anchored_end());
+
+        let regex = Regex::new("a^bc$d")?;
+        assert!(!regex.is_anchored_start());
+        assert!(!regex.is_anchored_end());
 
         Ok(())
     }
 
     #[test]
-    fn test_regex_space_class() -> Result<(), &'static str> {
-        // Space character
-        let space_regex = Regex::new("a[[:space:]]c").unwrap();
-
-        let space_line1 = space_regex.clone().evaluate(VALUE1)?;
-        let space_line2 = space_regex.clone().evaluate(VALUE2)?;
-        let space_line3 = space_regex.clone().evaluate(VALUE3)?;
-        let space_line4 = space_regex.clone().evaluate(VALUE4)?;
-        let space_line5 = space_regex.clone().evaluate(VALUE5)?;
-        let space_line6 = space_regex.evaluate(VALUE6)?;
-
-        assert!(!space_line1.result);
-        assert!(!space_line2.result);
-        assert!(!space_line3.result);
-        assert!(!space_line4.result);
-        assert!(space_line5.result);
-        assert!(!space_line6.result);
+    fn test_is_anchored_alternatives_and_groups() -> Result<(), &'static str> {
+        assert!(Regex::new("^a|^b")?.is_anchored_start());
+        assert!(!Regex::new("^a|b")?.is_anchored_start());
+        assert!(Regex::new("a$|(b$)")?.is_anchored_end());
+        assert!(Regex::new("(^a|^b)c")?.is_anchored_start());
+        assert!(!Regex::new("(^a)?c")?.is_anchored_start());
+        assert!(Regex::new_anchored("a|b")?.is_anchored_start());
 
         Ok(())
     }
 
     #[test]
-    fn test_regex_punct_class() -> Result<(), &'static str> {
-        // Punctuation character
-        let punct_regex = Regex::new("a[[:punct:]]c").unwrap();
+    fn test_wildcards_over_empty_string() -> Result<(), &'static str> {
+        for (pattern, expected) in [
+            (".*", true),
+            (".?", true),
+            ("a?", true),
+            ("a?.*", true),
+            ("^.*$", true),
+            (".", false),
+            (".+", false),
+            ("x.*", false),
+            (".*x", false),
+            (".?x", false),
+        ] {
+            let result = Regex::new(pattern)?.evaluate("")?.result;
+            assert_eq!(result, expected, "{}", pattern);
+        }
 
-        let punct_line1 = punct_regex.clone().evaluate(VALUE1)?;
-        let punct_line2 = punct_regex.clone().evaluate(VALUE2)?;
-        let punct_line3 = punct_regex.clone().evaluate(VALUE3)?;
-        let punct_line4 = punct_regex.clone().evaluate(VALUE4)?;
-        let punct_line5 = punct_regex.clone().evaluate(VALUE5)?;
-        let punct_line6 = punct_regex.evaluate(VALUE6)?;
+        Ok(())
+    }
 
-        assert!(!punct_line1.result);
-        assert!(!punct_line2.result);
-        assert!(punct_line3.result);
-        assert!(!punct_line4.result);
-        assert!(!punct_line5.result);
-        assert!(punct_line6.result);
+    #[test]
+    fn test_wildcards_around_literals() -> Result<(), &'static str> {
+        for (pattern, value, expected) in [
+            (".*a.*", "a", true),
+            (".?a.?", "a", true),
+            ("a.?", "a", true),
+            ("a?a", "a", true),
+            ("a.", "a", false),
+            (".a", "a", false),
+        ] {
+            let result = Regex::new(pattern)?.evaluate(value)?.result;
+            assert_eq!(result, expected, "{}", pattern);
+        }
 
         Ok(())
     }
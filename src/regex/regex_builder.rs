@@ -0,0 +1,142 @@
+use crate::regex::regex_options::RegexOptions;
+use crate::regex::Regex;
+
+#[derive(Debug, Clone)]
+pub struct RegexBuilder {
+    pattern: String,
+    options: RegexOptions,
+}
+
+impl RegexBuilder {
+    /// Given a pattern, returns a new RegexBuilder with the default options
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - A string that represents a regex
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::regex_builder::RegexBuilder;
+    ///
+    /// let regex = RegexBuilder::new("abc").build().unwrap();
+    /// ```
+    ///
+    pub fn new(pattern: &str) -> Self {
+        RegexBuilder {
+            pattern: pattern.to_string(),
+            options: RegexOptions::default(),
+        }
+    }
+
+    /// Sets if the regex ignores the case of the letters
+    ///
+    /// # Arguments
+    ///
+    /// * `case_insensitive` - If the case of the letters is ignored
+    ///
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.options.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Sets if the regex accepts non-ascii characters in the evaluated lines
+    ///
+    /// # Arguments
+    ///
+    /// * `unicode` - If non-ascii characters are accepted
+    ///
+    pub fn unicode(mut self, unicode: bool) -> Self {
+        self.options.unicode = unicode;
+        self
+    }
+
+    /// Sets if the regex is evaluated over a text of multiple lines.
+    /// In multiline mode the anchors match at the start and end of each line,
+    /// and the point does not match a newline unless dotall is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `multiline` - If the regex is evaluated over multiple lines
+    ///
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.options.multiline = multiline;
+        self
+    }
+
+    /// Sets if the point matches a newline in multiline mode
+    ///
+    /// # Arguments
+    ///
+    /// * `dotall` - If the point matches a newline
+    ///
+    pub fn dotall(mut self, dotall: bool) -> Self {
+        self.options.dotall = dotall;
+        self
+    }
+
+    /// Sets the maximum amount of match attempts of each search.
+    /// A search that needs more attempts fails with a complexity error instead of running indefinitely.
+    /// By default the amount of attempts is not limited.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_limit` - The maximum amount of match attempts
+    ///
+    pub fn step_limit(mut self, step_limit: usize) -> Self {
+        self.options.step_limit = Some(step_limit);
+        self
+    }
+
+    /// Sets if a '*', '+' or '?' without a preceding element is a literal character, as in grep.
+    /// By default it applies to a point, so a leading quantifier matches any characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `literal_leading_quantifiers` - If the leading quantifiers are literals
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::regex_builder::RegexBuilder;
+    ///
+    /// let regex = RegexBuilder::new("+1")
+    ///     .literal_leading_quantifiers(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(regex.evaluate("call +1").unwrap().result);
+    /// assert!(!regex.evaluate("call 1").unwrap().result);
+    /// ```
+    ///
+    pub fn literal_leading_quantifiers(mut self, literal_leading_quantifiers: bool) -> Self {
+        self.options.literal_leading_quantifiers = literal_leading_quantifiers;
+        self
+    }
+
+    /// Returns a new Regex with the pattern and options of the RegexBuilder
+    ///
+    /// # Returns
+    ///
+    /// * Regex - The corresponding Regex if the pattern is a valid regex
+    /// * &str - The corresponding error if the pattern is not a valid regex
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::regex_builder::RegexBuilder;
+    ///
+    /// let regex = RegexBuilder::new("abc")
+    ///     .case_insensitive(true)
+    ///     .unicode(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let line = regex.evaluate("ñABC").unwrap();
+    /// assert_eq!(line.result, true);
+    /// ```
+    ///
+    pub fn build(self) -> Result<Regex, &'static str> {
+        Regex::with_options(&self.pattern, &self.options)
+    }
+}
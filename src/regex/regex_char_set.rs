@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use super::regex_class::RegexClass;
+use super::regex_options::RegexOptions;
+
+#[derive(Debug, Clone, Default)]
+pub struct CharSet {
+    ascii: [u64; 4],
+    others: HashSet<char>,
+    classes: Vec<RegexClass>,
+}
+
+impl CharSet {
+    /// Given a char, adds it to the set.
+    /// ASCII chars are stored in a bitset, and the rest of the chars in a HashSet.
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - A char to be added
+    ///
+    pub fn insert(&mut self, c: char) {
+        if c.is_ascii() {
+            let code = c as usize;
+            self.ascii[code / 64] |= 1 << (code % 64);
+        } else {
+            self.others.insert(c);
+        }
+    }
+
+    /// Given a class, adds the class to the set.
+    /// The chars of the class are not inserted, the class is stored and checked when a char is matched
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - A RegexClass to be added
+    ///
+    pub fn insert_class(&mut self, class: RegexClass) {
+        self.classes.push(class);
+    }
+
+    /// Given a char, returns if it is in the set, either as a char or as part of a class
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - A char to be checked
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the char is in the set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::regex_char_set::*;
+    ///
+    /// let set = CharSet::from(vec!['a', 'b']);
+    ///
+    /// assert!(set.contains('a'));
+    /// assert!(!set.contains('c'));
+    /// ```
+    ///
+    pub fn contains(&self, c: char) -> bool {
+        self.contains_char(c) || self.classes.iter().any(|class| class.matches(c))
+    }
+
+    /// Given a char, returns if it was added to the set as a char
+    ///
+    fn contains_char(&self, c: char) -> bool {
+        if c.is_ascii() {
+            let code = c as usize;
+            self.ascii[code / 64] & (1 << (code % 64)) != 0
+        } else {
+            self.others.contains(&c)
+        }
+    }
+
+    /// Given a char and the options of a regex, returns if the char is in the set.
+    /// When the regex is case insensitive, the lowercase and uppercase versions of the char are also checked,
+    /// and the classes of the set are matched ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - A char to be checked
+    /// * `options` - The options of the regex
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the char is in the set
+    ///
+    pub fn contains_with(&self, c: char, options: &RegexOptions) -> bool {
+        if self.contains_char(c)
+            || self
+                .classes
+                .iter()
+                .any(|class| class.matches_with(c, options))
+        {
+            return true;
+        }
+
+        options.case_insensitive
+            && (c.to_lowercase().any(|lower| self.contains_char(lower))
+                || c.to_uppercase().any(|upper| self.contains_char(upper)))
+    }
+}
+
+impl From<Vec<char>> for CharSet {
+    fn from(chars: Vec<char>) -> Self {
+        let mut set = CharSet::default();
+        for c in chars {
+            set.insert(c);
+        }
+        set
+    }
+}
@@ -1,4 +1,5 @@
 use crate::regex::regex_error::RegexError;
+use crate::regex::regex_options::RegexOptions;
 
 #[derive(Debug, Clone)]
 pub enum RegexClass {
@@ -9,6 +10,7 @@ pub enum RegexClass {
     Upper,
     Space,
     Punct,
+    Word,
 }
 
 impl RegexClass {
@@ -42,6 +44,43 @@ impl RegexClass {
             RegexClass::Upper => c.is_uppercase(),
             RegexClass::Space => c.is_whitespace(),
             RegexClass::Punct => c.is_ascii_punctuation(),
+            RegexClass::Word => c.is_alphanumeric() || c == '_',
+        }
+    }
+
+    /// Given a char and the options of a regex, returns if it matches the RegexClass.
+    /// When the regex is case insensitive, the lower and upper classes match every letter with a case
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - A char to be checked
+    /// * `options` - The options of the regex
+    ///
+    /// # Returns
+    ///
+    /// * bool - If the char matches the RegexClass
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::regex_class::*;
+    /// use rgrep::regex::regex_options::*;
+    ///
+    /// let options = RegexOptions {
+    ///     case_insensitive: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert!(RegexClass::Upper.matches_with('a', &options));
+    /// assert!(!RegexClass::Upper.matches_with('a', &RegexOptions::default()));
+    /// ```
+    ///
+    pub fn matches_with(&self, c: char, options: &RegexOptions) -> bool {
+        match self {
+            RegexClass::Lower | RegexClass::Upper if options.case_insensitive => {
+                c.is_lowercase() || c.is_uppercase()
+            }
+            _ => self.matches(c),
         }
     }
 }
@@ -78,6 +117,7 @@ pub fn determinate_regex_class(class: String) -> Result<RegexClass, RegexError>
         "upper" => Ok(RegexClass::Upper),
         "space" => Ok(RegexClass::Space),
         "punct" => Ok(RegexClass::Punct),
+        "word" => Ok(RegexClass::Word),
         _ => Err(RegexError::InvalidClass),
     }
 }
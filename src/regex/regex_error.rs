@@ -5,6 +5,10 @@ pub enum RegexError {
     NoAsciiCharacter,
     InvalidBracket,
     InvalidClass,
+    InvalidRepetition,
+    InvalidGroup,
+    ComplexityLimitExceeded,
+    EmptyPattern,
 }
 
 impl RegexError {
@@ -31,6 +35,14 @@ impl RegexError {
             RegexError::NoAsciiCharacter => "Non-ascii characters in input",
             RegexError::InvalidBracket => "Invalid bracket in regex",
             RegexError::InvalidClass => "Invalid character class in regex",
+            RegexError::InvalidRepetition => {
+                "Invalid regex: repetition operator after another repetition"
+            }
+            RegexError::InvalidGroup => "Invalid group in regex",
+            RegexError::ComplexityLimitExceeded => {
+                "Regex too complex: match attempts limit exceeded"
+            }
+            RegexError::EmptyPattern => "Invalid regex: empty pattern",
         }
     }
 }
@@ -0,0 +1,9 @@
+#[derive(Debug, Clone, Default)]
+pub struct RegexOptions {
+    pub case_insensitive: bool,
+    pub unicode: bool,
+    pub multiline: bool,
+    pub dotall: bool,
+    pub step_limit: Option<usize>,
+    pub literal_leading_quantifiers: bool,
+}
@@ -7,3 +7,32 @@ pub enum RegexRep {
         max: Option<usize>,
     },
 }
+
+impl RegexRep {
+    /// Returns the minimum and maximum amount of repetitions allowed by the RegexRep
+    ///
+    /// # Returns
+    ///
+    /// * (usize, usize) - The minimum and maximum amount of repetitions, usize::MAX if unbounded
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::regex_rep::*;
+    ///
+    /// let rep = RegexRep::Range {
+    ///     min: Some(2),
+    ///     max: None,
+    /// };
+    ///
+    /// assert_eq!(rep.bounds(), (2, usize::MAX));
+    /// ```
+    ///
+    pub fn bounds(&self) -> (usize, usize) {
+        match self {
+            RegexRep::Any => (0, usize::MAX),
+            RegexRep::Exact(n) => (*n, *n),
+            RegexRep::Range { min, max } => (min.unwrap_or(0), max.unwrap_or(usize::MAX)),
+        }
+    }
+}
@@ -1,12 +1,22 @@
+use super::regex_char_set::CharSet;
 use super::regex_class::RegexClass;
+use super::regex_options::RegexOptions;
+use super::RegexStep;
 
 #[derive(Debug, Clone)]
 pub enum RegexVal {
     Literal(char),
     Wildcard,
     Class(RegexClass),
-    Bracket(Vec<char>),
-    NotBracket(Vec<char>),
+    NotClass(RegexClass),
+    Bracket(CharSet),
+    NotBracket(CharSet),
+    Group { index: usize, steps: Vec<RegexStep> },
+    Alternation(Vec<Vec<RegexStep>>),
+    WordStart,
+    WordEnd,
+    TextStart,
+    TextEnd,
 }
 
 impl RegexVal {
@@ -18,7 +28,9 @@ impl RegexVal {
     ///
     /// # Returns
     ///
-    /// * usize - The size of the amount of characters that match the RegexVal
+    /// * Option<usize> - The size of the amount of characters that match the RegexVal,
+    ///   None if it does not match. A match always consumes a character, the zero width values
+    ///   depend on the position in the text and are matched by the regex itself
     ///
     /// # Examples
     ///
@@ -27,59 +39,78 @@ impl RegexVal {
     ///
     /// let regex_val = RegexVal::Literal('a');
     ///
-    /// let value = "abc";
-    /// assert_eq!(regex_val.matches(value), 1);
+    /// assert_eq!(regex_val.matches("abc"), Some(1));
+    /// assert_eq!(regex_val.matches("bc"), None);
+    /// assert_eq!(regex_val.matches(""), None);
     /// ```
     ///
-    pub fn matches(&self, value: &str) -> usize {
-        match self {
-            RegexVal::Literal(l) => {
-                if value.starts_with(*l) {
-                    l.len_utf8()
-                } else {
-                    0
-                }
-            }
-            RegexVal::Wildcard => {
-                if let Some(c) = value.chars().next() {
-                    c.len_utf8()
-                } else {
-                    0
-                }
-            }
-            RegexVal::Class(class) => {
-                if let Some(c) = value.chars().next() {
-                    if class.matches(c) {
-                        c.len_utf8()
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                }
-            }
-            RegexVal::Bracket(vec) => {
-                for c in vec {
-                    if value.starts_with(*c) {
-                        return c.len_utf8();
-                    }
-                }
-                0
-            }
-            RegexVal::NotBracket(vec) => {
-                for c in vec {
-                    if value.starts_with(*c) {
-                        return 0;
-                    }
-                }
+    pub fn matches(&self, value: &str) -> Option<usize> {
+        self.matches_with(value, &RegexOptions::default())
+    }
 
-                let next_char = value.chars().next();
-                if let Some(c) = next_char {
-                    c.len_utf8()
-                } else {
-                    0
-                }
-            }
+    /// Given a string and the options of a regex, returns the size of the amount of characters
+    /// that match the RegexVal
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A string to be checked
+    /// * `options` - The options of the regex
+    ///
+    /// # Returns
+    ///
+    /// * Option<usize> - The size of the amount of characters that match the RegexVal,
+    ///   None if it does not match
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgrep::regex::regex_options::*;
+    /// use rgrep::regex::regex_val::*;
+    ///
+    /// let regex_val = RegexVal::Literal('a');
+    /// let options = RegexOptions {
+    ///     case_insensitive: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let value = "Abc";
+    /// assert_eq!(regex_val.matches_with(value, &options), Some(1));
+    /// ```
+    ///
+    pub fn matches_with(&self, value: &str, options: &RegexOptions) -> Option<usize> {
+        let next_char = value.chars().next()?;
+
+        let is_match = match self {
+            RegexVal::Literal(l) => same_char(*l, next_char, options),
+            RegexVal::Wildcard => options.dotall || !(options.multiline && next_char == '\n'),
+            RegexVal::Class(class) => class.matches_with(next_char, options),
+            RegexVal::NotClass(class) => !class.matches_with(next_char, options),
+            RegexVal::Bracket(set) => set.contains_with(next_char, options),
+            RegexVal::NotBracket(set) => !set.contains_with(next_char, options),
+            // A group or an alternation can match a variable amount of characters,
+            // and a boundary depends on the position in the text, they are matched by the regex itself
+            RegexVal::Group { .. }
+            | RegexVal::Alternation(_)
+            | RegexVal::WordStart
+            | RegexVal::WordEnd
+            | RegexVal::TextStart
+            | RegexVal::TextEnd => false,
+        };
+
+        if is_match {
+            Some(next_char.len_utf8())
+        } else {
+            None
         }
     }
 }
+
+/// Given two chars and the options of a regex, returns if both chars are considered the same
+///
+fn same_char(expected: char, found: char, options: &RegexOptions) -> bool {
+    if options.case_insensitive {
+        expected.to_lowercase().eq(found.to_lowercase())
+    } else {
+        expected == found
+    }
+}
@@ -0,0 +1,56 @@
+// Performance tests, ignored by default. Run with:
+// cargo test --test benchmark_tests -- --ignored --nocapture
+use rgrep::*;
+
+use std::time::{Duration, Instant};
+
+const LINES: usize = 50_000;
+const THRESHOLD: Duration = Duration::from_secs(30);
+
+fn generar_texto() -> String {
+    (0..LINES)
+        .map(|i| match i % 4 {
+            0 => format!("abc line {} with some text", i),
+            1 => format!("phone {:03}-{:04} registered", i % 1000, i % 10000),
+            2 => "the quick brown fox jumps over the lazy dog".to_string(),
+            _ => format!("{} repeated line", "x".repeat(i % 100)),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[test]
+#[ignore]
+fn test_rendimiento_patrones_representativos() {
+    let text = generar_texto();
+    let patterns = [
+        ("anclado", "^abc"),
+        ("anclado al final", "registered$"),
+        ("comodin", "q.*k.*z.*y"),
+        ("clase", "[[:digit:]]{3}-[[:digit:]]{4}"),
+        ("alternativas", "fox|phone|zzz"),
+        ("repeticiones", "x*x*x*y"),
+    ];
+
+    for (name, pattern) in patterns {
+        let start = Instant::now();
+        let lines = run_rgrep(pattern, &text).unwrap();
+        let elapsed = start.elapsed();
+
+        println!("{:<20} {:>8} lineas {:?}", name, lines.len(), elapsed);
+        assert!(elapsed < THRESHOLD, "{} tardo {:?}", pattern, elapsed);
+    }
+}
+
+#[test]
+#[ignore]
+fn test_rendimiento_lineas_unicas() {
+    let text = generar_texto();
+
+    let start = Instant::now();
+    let lines = run_rgrep_unique("line", &text, '\n').unwrap();
+    let elapsed = start.elapsed();
+
+    println!("{:<20} {:>8} lineas {:?}", "unicas", lines.len(), elapsed);
+    assert!(elapsed < THRESHOLD);
+}
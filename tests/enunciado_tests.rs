@@ -5,7 +5,7 @@ fn test_enunciado_1() {
     let regex = "ab.cd".to_string();
     let lines = "abcd\nabecd\nabccd".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(program_output[0], "abecd");
@@ -17,7 +17,7 @@ fn test_enunciado_2() {
     let regex = "ab.*cd".to_string();
     let lines = "abcd\nabecd\nabccd\nabeeeeeecd".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 4);
     assert_eq!(program_output[0], "abcd");
@@ -31,7 +31,7 @@ fn test_enunciado_3() {
     let regex = "a[bc]d".to_string();
     let lines = "abcd\nabd\nacd\nad\nabbbcccd".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(program_output[0], "abd");
@@ -43,7 +43,7 @@ fn test_enunciado_4() {
     let regex = "ab{2,4}cd".to_string();
     let lines = "abcd\nabbcd\nabbbcd\naeecd\nabbbbcd\nabbbbbcd\nacd".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 3);
     assert_eq!(program_output[0], "abbcd");
@@ -56,7 +56,7 @@ fn test_enunciado_5() {
     let regex = "abc|de+f".to_string();
     let lines = "abcd\nabbcd\nrabcr\ndfac\nadef\nzadeeefj\nabcdef".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 5);
     assert_eq!(program_output[0], "abcd");
@@ -71,7 +71,7 @@ fn test_enunciado_6() {
     let regex = "la [aeiou] es una vocal".to_string();
     let lines = "la a es una vocal\nla e es una vocal\nla i es una vocal\nla o es una vocal\nla u es una vocal\nla r es una vocal\nla   es una vocal\nla % es una vocal\nla 4 es una vocal".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 5);
     assert_eq!(program_output[0], "la a es una vocal");
@@ -86,7 +86,7 @@ fn test_enunciado_7() {
     let regex = "la [^aeiou] no es una vocal".to_string();
     let lines = "la a no es una vocal\nla e no es una vocal\nla i no es una vocal\nla o no es una vocal\nla u no es una vocal\nla z no es una vocal\nla   no es una vocal\nla ! no es una vocal\nla 8 no es una vocal".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 4);
     assert_eq!(program_output[0], "la z no es una vocal");
@@ -100,7 +100,7 @@ fn test_enunciado_8() {
     let regex = "hola [[:alpha:]]+".to_string();
     let lines = "hola mundo\nhola 123\nhola\nhola 123 mundo\n123 hola mundo\nhola !".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(program_output[0], "hola mundo");
@@ -112,7 +112,7 @@ fn test_enunciado_9() {
     let regex = "[[:digit:]] es un numero".to_string();
     let lines = "1 es un numero\n2 es un numero\n3 es un numero\nel 4 es un numero\n5 es un numero!\nel 6 es un numero tambien\n7 es un numero\n8 es un numero\n9 es un numero\n0 es un numero\na es un numero\n! es un numero\n  es un numero".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 10);
     assert_eq!(program_output[0], "1 es un numero");
@@ -132,7 +132,7 @@ fn test_enunciado_10() {
     let regex = "el caracter [[:alnum:]] no es un simbolo".to_string();
     let lines = "el caracter a no es un simbolo\nel caracter 1 no es un simbolo\nel caracter ! no es un simbolo\nel caracter   no es un simbolo\nefectivamente el caracter P no es un simbolo!".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 3);
     assert_eq!(program_output[0], "el caracter a no es un simbolo");
@@ -149,7 +149,7 @@ fn test_enunciado_11() {
     let lines = "hola mundo\nholamundo\nhey hola mundo !\nHola mundo\n(hola mundo)\nhola  mundo"
         .to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 3);
     assert_eq!(program_output[0], "hola mundo");
@@ -162,7 +162,7 @@ fn test_enunciado_12() {
     let regex = "[[:upper:]]ascal[[:upper:]]ase".to_string();
     let lines = "CascalCase\nbascalcase\n3ascal8ase\nthis is PascalRase yeah!\nascalase\n ascal ase\n?ascal!ase".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(program_output[0], "CascalCase");
@@ -174,7 +174,7 @@ fn test_enunciado_13() {
     let regex = "es el fin$".to_string();
     let lines = "es el fin\nefectivamente, es el fin\nes el fin... o no\nno es el fin \nthis is fin\nsera? si, es el fin!\n".to_string();
 
-    let program_output = run_rgrep(regex, lines).unwrap();
+    let program_output = run_rgrep(&regex, &lines).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(program_output[0], "es el fin");
@@ -1,3 +1,4 @@
+use rgrep::program_error::ProgramError;
 use rgrep::*;
 
 #[test]
@@ -6,7 +7,7 @@ fn test_funcionamiento_general() {
     let path_file = "res/test0.txt".to_string();
 
     let file_text = read_file(path_file).unwrap();
-    let program_output = run_rgrep(regex, file_text).unwrap();
+    let program_output = run_rgrep(&regex, &file_text).unwrap();
 
     assert_eq!(program_output.len(), 3);
     assert_eq!(program_output[0], "regex");
@@ -20,7 +21,7 @@ fn tests_funcionamiento_pipe_simple() {
     let file_text = read_file(path_file).unwrap();
 
     let regex = "z|o".to_string();
-    let program_output = run_rgrep(regex, file_text.clone()).unwrap();
+    let program_output = run_rgrep(&regex, &file_text).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(
@@ -36,7 +37,7 @@ fn tests_funcionamiento_pipe_multiples_match() {
     let file_text = read_file(path_file).unwrap();
 
     let regex = "z|o|regex".to_string();
-    let program_output = run_rgrep(regex, file_text.clone()).unwrap();
+    let program_output = run_rgrep(&regex, &file_text).unwrap();
 
     println!("{:?}", program_output);
     assert_eq!(program_output.len(), 4);
@@ -55,7 +56,7 @@ fn tests_funcionamiento_pipe_start_and_end() {
     let file_text = read_file(path_file).unwrap();
 
     let regex = "|a|regex|".to_string();
-    let program_output = run_rgrep(regex, file_text.clone()).unwrap();
+    let program_output = run_rgrep(&regex, &file_text).unwrap();
 
     assert_eq!(program_output.len(), 5);
     assert_eq!(
@@ -74,7 +75,7 @@ fn tests_funcionamiento_backslash_y_pipe() {
     let file_text = read_file(path_file).unwrap();
 
     let regex = "z|a|e\\|o".to_string();
-    let program_output = run_rgrep(regex, file_text.clone()).unwrap();
+    let program_output = run_rgrep(&regex, &file_text).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(
@@ -90,7 +91,7 @@ fn tests_funcionamiento_backslash_on_file() {
     let file_text = read_file(path_file).unwrap();
 
     let regex = "z|a|e\\|o".to_string();
-    let program_output = run_rgrep(regex, file_text.clone()).unwrap();
+    let program_output = run_rgrep(&regex, &file_text).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(program_output[0], "aaa");
@@ -103,7 +104,7 @@ fn tests_funcionamiento_backslash_at_the_end() {
     let file_text = read_file(path_file).unwrap();
 
     let regex = "z|q\\|".to_string();
-    let program_output = run_rgrep(regex, file_text.clone()).unwrap();
+    let program_output = run_rgrep(&regex, &file_text).unwrap();
 
     assert_eq!(program_output.len(), 1);
     assert_eq!(program_output[0], "qqqq|");
@@ -114,7 +115,7 @@ fn test_correcciones_entrega_1() {
     let regex = "ab.?d".to_string();
     let text = "abcd\nabcdd\nabd\nhola abcd chau\nabhhd".to_string();
 
-    let program_output = run_rgrep(regex, text).unwrap();
+    let program_output = run_rgrep(&regex, &text).unwrap();
 
     assert_eq!(program_output.len(), 4);
     assert_eq!(program_output[0], "abcd");
@@ -128,7 +129,7 @@ fn test_correcciones_entrega_2() {
     let regex = "ab.d".to_string();
     let text = "abcd\nabcdd\nabccd\nhola abcd chau".to_string();
 
-    let program_output = run_rgrep(regex, text).unwrap();
+    let program_output = run_rgrep(&regex, &text).unwrap();
 
     assert_eq!(program_output.len(), 3);
     assert_eq!(program_output[0], "abcd");
@@ -141,7 +142,7 @@ fn test_correcciones_entrega_3() {
     let regex = "abc{3}d".to_string();
     let text = "abcd\nabcccd\nhola abcccd chau".to_string();
 
-    let program_output = run_rgrep(regex, text).unwrap();
+    let program_output = run_rgrep(&regex, &text).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(program_output[0], "abcccd");
@@ -153,7 +154,7 @@ fn test_correcciones_entrega_4() {
     let regex = "^start|end$".to_string();
     let text = "start middle end\nstart with start\nend with end\nonly this line".to_string();
 
-    let program_output = run_rgrep(regex, text).unwrap();
+    let program_output = run_rgrep(&regex, &text).unwrap();
 
     assert_eq!(program_output.len(), 3);
     assert_eq!(program_output[0], "start middle end");
@@ -167,7 +168,7 @@ fn test_correcciones_entrega_5() {
     let text = "start middle end\nstart with whatever but end not\nend with end\nonly this line"
         .to_string();
 
-    let program_output = run_rgrep(regex, text).unwrap();
+    let program_output = run_rgrep(&regex, &text).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(program_output[0], "start middle end");
@@ -179,7 +180,7 @@ fn test_correcciones_entrega_6() {
     let regex = "[[:punct:]]".to_string();
     let text = "abc!123\nAB-12\n123\nabc-123".to_string();
 
-    let program_output = run_rgrep(regex, text).unwrap();
+    let program_output = run_rgrep(&regex, &text).unwrap();
 
     assert_eq!(program_output.len(), 3);
     assert_eq!(program_output[0], "abc!123");
@@ -192,9 +193,1207 @@ fn test_correcciones_entrega_7() {
     let regex = "[[:lower:]]".to_string();
     let text = "abc123\nAB12\n123\nabc-123".to_string();
 
-    let program_output = run_rgrep(regex, text).unwrap();
+    let program_output = run_rgrep(&regex, &text).unwrap();
 
     assert_eq!(program_output.len(), 2);
     assert_eq!(program_output[0], "abc123");
     assert_eq!(program_output[1], "abc-123");
 }
+
+#[test]
+fn test_funcionamiento_archivo_binario() {
+    let binding = { vec!["rgrep", "abc", "res/binary.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::new(args).unwrap();
+
+    let program_output = search_file(&arguments).unwrap();
+
+    assert_eq!(program_output.len(), 1);
+    assert_eq!(program_output[0], "Binary file res/binary.txt matches");
+}
+
+#[test]
+fn test_funcionamiento_archivo_binario_sin_match() {
+    let binding = { vec!["rgrep", "zzz", "res/binary.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::new(args).unwrap();
+
+    let program_output = search_file(&arguments).unwrap();
+
+    assert!(program_output.is_empty());
+}
+
+#[test]
+fn test_funcionamiento_archivo_binario_como_texto() {
+    let binding = { vec!["rgrep", "-a", "abc", "res/binary.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::new(args).unwrap();
+
+    let program_output = search_file(&arguments).unwrap();
+
+    assert_eq!(program_output.len(), 2);
+    assert_eq!(program_output[0], "abc\0def");
+    assert_eq!(program_output[1], "abcd");
+}
+
+#[test]
+fn test_funcionamiento_directorio_recursivo() {
+    let binding = { vec!["rgrep", "-r", "regex", "res/dir"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::new(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines.len(), 3);
+    assert_eq!(program_output.lines[0], "res/dir/a.txt:regex one");
+    assert_eq!(program_output.lines[1], "res/dir/a.txt:regex two");
+    assert_eq!(program_output.lines[2], "res/dir/sub/c.txt:deep regex");
+    assert!(program_output.errors.is_empty());
+}
+
+#[test]
+fn test_funcionamiento_stats() {
+    let binding = { vec!["rgrep", "-r", "--stats", "regex", "res/dir"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::new(args).unwrap();
+    assert!(arguments.stats);
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.stats,
+        SearchStats {
+            files_searched: 3,
+            files_matched: 2,
+            matching_lines: 3,
+        }
+    );
+    assert_eq!(
+        program_output.stats.summary(),
+        "searched 3 files, 2 matched, 3 matching lines"
+    );
+}
+
+#[test]
+fn test_funcionamiento_max_depth() {
+    let binding = { vec!["rgrep", "-r", "--max-depth", "0", "regex", "res/dir"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::new(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.stats.files_searched, 2);
+    assert_eq!(program_output.lines.len(), 2);
+    assert_eq!(program_output.lines[0], "res/dir/a.txt:regex one");
+    assert_eq!(program_output.lines[1], "res/dir/a.txt:regex two");
+
+    let binding = { vec!["rgrep", "-r", "--max-depth", "1", "regex", "res/dir"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::new(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.stats.files_searched, 3);
+    assert_eq!(program_output.lines[2], "res/dir/sub/c.txt:deep regex");
+}
+
+#[test]
+fn test_funcionamiento_multiples_archivos() {
+    let binding = { vec!["rgrep", "regex", "res/dir/a.txt", "res/dir/sub/c.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.lines,
+        vec![
+            "res/dir/a.txt:regex one",
+            "res/dir/a.txt:regex two",
+            "res/dir/sub/c.txt:deep regex"
+        ]
+    );
+
+    let binding = { vec!["rgrep", "regex", "res/dir/a.txt", "res/missing.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines.len(), 2);
+    assert_eq!(program_output.errors.len(), 1);
+    assert!(program_output.errors[0].starts_with("res/missing.txt: "));
+}
+
+#[test]
+fn test_funcionamiento_color() {
+    let binding = { vec!["rgrep", "--color=always", "regex", "res/dir/a.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.lines[0],
+        "\x1b[01;31mregex\x1b[0m one".to_string()
+    );
+    assert!(program_output
+        .lines
+        .iter()
+        .all(|line| line.contains("\x1b[01;31m")));
+
+    let binding = { vec!["rgrep", "--color=never", "regex", "res/dir/a.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines[0], "regex one".to_string());
+    assert!(!program_output
+        .lines
+        .iter()
+        .any(|line| line.contains('\x1b')));
+}
+
+#[test]
+fn test_funcionamiento_count() {
+    let binding = { vec!["rgrep", "-c", "regex", "res/dir/a.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines, vec!["2"]);
+}
+
+#[test]
+fn test_funcionamiento_count_multiples_archivos() {
+    let binding = { vec!["rgrep", "-c", "regex", "res/dir/a.txt", "res/dir/b.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.lines,
+        vec!["res/dir/a.txt:2", "res/dir/b.txt:0"]
+    );
+}
+
+#[test]
+fn test_funcionamiento_numero_de_linea() {
+    let binding = { vec!["rgrep", "-n", "regex", "res/dir/a.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines, vec!["1:regex one", "3:regex two"]);
+
+    let binding = {
+        vec![
+            "rgrep",
+            "-n",
+            "--field-separator",
+            "\t",
+            "regex",
+            "res/dir/a.txt",
+            "res/dir/sub/c.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.lines,
+        vec![
+            "res/dir/a.txt\t1\tregex one",
+            "res/dir/a.txt\t3\tregex two",
+            "res/dir/sub/c.txt\t1\tdeep regex"
+        ]
+    );
+}
+
+#[test]
+fn test_funcionamiento_archivo_vacio() {
+    let file_text = read_file("res/empty.txt".to_string()).unwrap();
+    assert_eq!(file_text, "");
+
+    let program_output = run_rgrep("regex", &file_text).unwrap();
+    assert!(program_output.is_empty());
+
+    let program_output = run_rgrep(".*", &file_text).unwrap();
+    assert_eq!(program_output, vec![""]);
+
+    let binding = { vec!["rgrep", "regex", "res/empty.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert!(program_output.lines.is_empty());
+    assert!(program_output.errors.is_empty());
+}
+
+#[test]
+fn test_funcionamiento_aviso_archivo_vacio() {
+    let binding = { vec!["rgrep", "--warn-empty", "regex", "res/empty.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let error = run_search(&arguments).unwrap_err();
+    assert_eq!(error, ProgramError::EmptyFile.message());
+
+    let binding = {
+        vec![
+            "rgrep",
+            "--warn-empty",
+            "regex",
+            "res/empty.txt",
+            "res/dir/a.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines.len(), 2);
+    assert_eq!(
+        program_output.errors,
+        vec![format!(
+            "res/empty.txt: {}",
+            ProgramError::EmptyFile.message()
+        )]
+    );
+}
+
+#[test]
+fn test_funcionamiento_invertir_con_conteo() {
+    let file_text = read_file("res/test0.txt".to_string()).unwrap();
+    let total_lines = file_text.lines().count();
+    let matching_lines = run_rgrep("regex", &file_text).unwrap().len();
+
+    let binding = { vec!["rgrep", "-v", "-c", "regex", "res/test0.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.lines,
+        vec![(total_lines - matching_lines).to_string()]
+    );
+}
+
+#[test]
+fn test_funcionamiento_invertir() {
+    let binding = { vec!["rgrep", "-v", "-n", "regex", "res/dir/a.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines, vec!["2:nothing"]);
+}
+
+#[test]
+fn test_funcionamiento_texto_literal() {
+    let binding = { vec!["rgrep", "-F", "a.c", "res/fixed.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines, vec!["a.c", "abc|a.c"]);
+}
+
+#[test]
+fn test_funcionamiento_texto_literal_con_barra() {
+    let binding = { vec!["rgrep", "--fixed-strings", "a|c", "res/fixed.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines, vec!["a|c"]);
+}
+
+#[test]
+fn test_funcionamiento_sin_nombre_de_archivo() {
+    let binding = { vec!["rgrep", "-h", "regex", "res/dir/a.txt", "res/dir/sub/c.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.lines,
+        vec!["regex one", "regex two", "deep regex"]
+    );
+
+    let binding = {
+        vec![
+            "rgrep",
+            "--no-filename",
+            "-c",
+            "regex",
+            "res/dir/a.txt",
+            "res/dir/b.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines, vec!["2", "0"]);
+}
+
+#[test]
+fn test_funcionamiento_con_nombre_de_archivo() {
+    let binding = { vec!["rgrep", "-H", "regex", "res/dir/a.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.lines,
+        vec!["res/dir/a.txt:regex one", "res/dir/a.txt:regex two"]
+    );
+
+    let binding = { vec!["rgrep", "--with-filename", "-c", "regex", "res/dir/a.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines, vec!["res/dir/a.txt:2"]);
+}
+
+#[test]
+fn test_funcionamiento_lineas_repetidas() {
+    let binding = { vec!["rgrep", "-n", "regex", "res/duplicates.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.lines,
+        vec!["1:regex one", "3:regex one", "4:regex two"]
+    );
+
+    let binding = { vec!["rgrep", "--unique", "regex", "res/duplicates.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines, vec!["regex one", "regex two"]);
+}
+
+#[test]
+fn test_funcionamiento_solo_coincidencias() {
+    let binding = { vec!["rgrep", "-o", "-n", "re.ex|two", "res/dir/a.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(program_output.lines, vec!["1:regex", "3:regex", "3:two"]);
+}
+
+#[test]
+fn test_funcionamiento_archivos_con_coincidencias() {
+    let binding = {
+        vec![
+            "rgrep",
+            "-l",
+            "regex",
+            "res/dir/a.txt",
+            "res/dir/b.txt",
+            "res/dir/sub/c.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+
+    assert_eq!(
+        program_output.lines,
+        vec!["res/dir/a.txt", "res/dir/sub/c.txt"]
+    );
+}
+
+#[test]
+fn test_funcionamiento_codigo_de_salida_con_conteo() {
+    let binding = { vec!["rgrep", "-c", "regex", "res/dir/a.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["2"]);
+    assert_eq!(program_output.exit_code(), EXIT_MATCH);
+
+    let binding = { vec!["rgrep", "-c", "regex", "res/dir/b.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["0"]);
+    assert_eq!(program_output.exit_code(), EXIT_NO_MATCH);
+}
+
+#[test]
+fn test_funcionamiento_codigo_de_salida_del_programa() {
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_rgrep"))
+        .args(["-c", "regex", "res/dir/a.txt"])
+        .output()
+        .unwrap()
+        .status;
+    assert_eq!(status.code(), Some(EXIT_MATCH));
+
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_rgrep"))
+        .args(["-c", "regex", "res/dir/b.txt"])
+        .output()
+        .unwrap()
+        .status;
+    assert_eq!(status.code(), Some(EXIT_NO_MATCH));
+
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_rgrep"))
+        .args(["regex", "res/missing.txt"])
+        .output()
+        .unwrap()
+        .status;
+    assert_eq!(status.code(), Some(EXIT_ERROR));
+}
+
+#[test]
+fn test_funcionamiento_line_buffered_imprime_antes_del_fin_de_la_entrada() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rgrep"))
+        .args(["--line-buffered", "-n", "ab", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let reader = std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            sender.send(line.unwrap()).unwrap();
+        }
+    });
+
+    // The input is still open, so each line can only be printed if it is found while reading
+    stdin.write_all(b"xyz\nabc\n").unwrap();
+    stdin.flush().unwrap();
+    let timeout = std::time::Duration::from_secs(10);
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), "2:abc");
+
+    stdin.write_all(b"zab\n").unwrap();
+    stdin.flush().unwrap();
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), "3:zab");
+
+    drop(stdin);
+    assert_eq!(child.wait().unwrap().code(), Some(EXIT_MATCH));
+    reader.join().unwrap();
+}
+
+#[test]
+fn test_funcionamiento_codigo_de_salida_con_coincidencias_y_errores() {
+    let binding = { vec!["rgrep", "aaa", "res/test2.txt", "nope.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert!(!program_output.lines.is_empty());
+    assert_eq!(program_output.errors.len(), 1);
+    assert_eq!(program_output.exit_code(), EXIT_ERROR);
+
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_rgrep"))
+        .args(["aaa", "res/test2.txt", "nope.txt"])
+        .output()
+        .unwrap()
+        .status;
+    assert_eq!(status.code(), Some(EXIT_ERROR));
+}
+
+#[test]
+fn test_funcionamiento_codificacion_latin1() {
+    let binding = {
+        vec![
+            "rgrep",
+            "--encoding",
+            "latin1",
+            "caf. regex",
+            "res/latin1.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["café regex"]);
+
+    let binding = { vec!["rgrep", "caf. regex", "res/latin1.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let err = run_search(&arguments).unwrap_err();
+    assert_eq!(err, ProgramError::InvalidFileFormat.message());
+}
+
+#[test]
+fn test_funcionamiento_codificacion_latin1_multilinea_y_tsv() {
+    let binding = {
+        vec![
+            "rgrep",
+            "--encoding",
+            "latin1",
+            "--multiline",
+            "caf",
+            "res/latin1.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["caf"]);
+
+    let binding = {
+        vec![
+            "rgrep",
+            "--encoding",
+            "latin1",
+            "--tsv",
+            "caf",
+            "res/latin1.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["1\t1\tcafé regex"]);
+}
+
+#[test]
+fn test_funcionamiento_linea_muy_larga() {
+    let regex = "a*a*a*b".to_string();
+    let long_line = "a".repeat(100_000);
+    let start = std::time::Instant::now();
+
+    let program_output = run_rgrep(&regex, &long_line).unwrap();
+    assert!(program_output.is_empty());
+
+    let matching_line = format!("{}b", long_line);
+    let program_output = run_rgrep(&regex, &matching_line).unwrap();
+    assert_eq!(program_output, vec![matching_line.as_str()]);
+
+    let program_output = run_rgrep("x|a*a*a*ba", &matching_line).unwrap();
+    assert!(program_output.is_empty());
+
+    let program_output = run_rgrep("(a*)*bx|c", &matching_line).unwrap();
+    assert!(program_output.is_empty());
+
+    assert!(start.elapsed() < std::time::Duration::from_secs(10));
+}
+
+#[test]
+fn test_funcionamiento_patron_que_empieza_con_guion() {
+    let binding = { vec!["rgrep", "--", "-abc", "res/dashes.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    assert_eq!(arguments.regex, "-abc");
+    assert_eq!(arguments.paths, vec!["res/dashes.txt"]);
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["-abc", "x -abc y"]);
+}
+
+#[test]
+fn test_funcionamiento_multiples_patrones() {
+    let binding = { vec!["rgrep", "-e", "abc", "-e", "def", "res/patterns.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["abc", "def", "xdefx"]);
+
+    let binding = { vec!["rgrep", "-e", "^d", "-e", "ef$", "res/patterns.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["def", "ab cd ef"]);
+}
+
+#[test]
+fn test_funcionamiento_archivo_de_patrones() {
+    let binding = { vec!["rgrep", "-f", "res/pattern_list.txt", "res/patterns.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["abc", "def", "xdefx"]);
+}
+
+#[test]
+fn test_funcionamiento_ignorar_patrones_invalidos() {
+    let binding = {
+        vec![
+            "rgrep",
+            "--ignore-bad-patterns",
+            "abc|[",
+            "res/patterns.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["abc"]);
+    assert_eq!(program_output.errors.len(), 1);
+    assert!(program_output.errors[0].starts_with("[: "));
+    assert_eq!(program_output.exit_code(), EXIT_ERROR);
+
+    let binding = { vec!["rgrep", "abc|[", "res/patterns.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    assert!(run_search(&arguments).is_err());
+}
+
+#[test]
+fn test_funcionamiento_lineas_de_contexto() {
+    let binding = { vec!["rgrep", "-C", "1", "match", "res/context.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec!["one", "match a", "two", "match b", "three", "--", "five", "match c"]
+    );
+    assert_eq!(program_output.stats.matching_lines, 3);
+
+    let binding = { vec!["rgrep", "-n", "-C", "1", "match b", "res/context.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["3-two", "4:match b", "5-three"]);
+}
+
+#[test]
+fn test_funcionamiento_salida_tsv() {
+    let binding = { vec!["rgrep", "--tsv", "[[:alpha:]]c", "res/dashes.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec!["1\t1\t-abc", "2\t1\tabc", "3\t1\tx -abc y"]
+    );
+}
+
+#[test]
+fn test_funcionamiento_lineas_unicas_entre_archivos() {
+    let binding = { vec!["rgrep", "regex", "res/dir/a.txt", "res/duplicates.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec![
+            "res/dir/a.txt:regex one",
+            "res/dir/a.txt:regex two",
+            "res/duplicates.txt:regex one",
+            "res/duplicates.txt:regex one",
+            "res/duplicates.txt:regex two",
+        ]
+    );
+
+    let binding = {
+        vec![
+            "rgrep",
+            "--unique-global",
+            "regex",
+            "res/dir/a.txt",
+            "res/duplicates.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec!["res/dir/a.txt:regex one", "res/dir/a.txt:regex two"]
+    );
+}
+
+#[test]
+fn test_funcionamiento_columna_de_coincidencia() {
+    let binding = { vec!["rgrep", "--column", "cd", "res/columns.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["1:3:abcdef", "3:4:xx cd cd"]);
+
+    let binding = {
+        vec![
+            "rgrep",
+            "--column",
+            "regex",
+            "res/latin1.txt",
+            "--encoding",
+            "latin1",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["1:6:café regex"]);
+}
+
+#[test]
+fn test_funcionamiento_utf8_con_cualquier_modo_de_busqueda() {
+    let searches: [(&[&str], Vec<String>); 4] = [
+        (&[], vec!["café regex".to_string(), "niño".to_string()]),
+        (&["-o"], vec!["é".to_string(), "ñ".to_string()]),
+        (
+            &["--tsv"],
+            vec!["1\t1\tcafé regex".to_string(), "2\t1\tniño".to_string()],
+        ),
+        (
+            &["--color=always"],
+            vec![
+                "caf\x1b[01;31mé\x1b[0m regex".to_string(),
+                "ni\x1b[01;31mñ\x1b[0mo".to_string(),
+            ],
+        ),
+    ];
+
+    for (flags, expected) in searches {
+        let mut binding = vec!["rgrep"];
+        binding.extend(flags);
+        binding.extend(["é|ñ", "res/utf8.txt"]);
+        let args = binding.iter().map(|s| s.to_string());
+        let arguments = Arguments::parse(args).unwrap();
+
+        let program_output = run_search(&arguments).unwrap();
+        assert_eq!(program_output.lines, expected);
+    }
+}
+
+#[test]
+fn test_funcionamiento_seguir_enlaces_simbolicos() {
+    let binding = { vec!["rgrep", "-r", "regex", "res/links"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["res/links/file.txt:regex link"]);
+
+    let binding = { vec!["rgrep", "-r", "-S", "regex", "res/links"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec![
+            "res/links/file.txt:regex link",
+            "res/links/target/c.txt:deep regex",
+        ]
+    );
+}
+
+#[test]
+fn test_funcionamiento_buscar_en_nombres_de_archivo() {
+    let binding = { vec!["rgrep", "-r", "--match-filename", "\\.rs$", "res/sources"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec!["res/sources/main.rs", "res/sources/nested/lib.rs"]
+    );
+    assert_eq!(program_output.stats.files_searched, 4);
+    assert_eq!(program_output.exit_code(), EXIT_MATCH);
+}
+
+#[test]
+fn test_funcionamiento_largo_maximo_de_linea() {
+    let binding = {
+        vec![
+            "rgrep",
+            "--max-line-length",
+            "10",
+            "-n",
+            "regex",
+            "res/test0.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec!["3:regex", "4:no regex", "5:multiple..."]
+    );
+    assert_eq!(program_output.stats.matching_lines, 3);
+}
+
+#[test]
+fn test_funcionamiento_resultado_detallado() {
+    let file_text = read_file("res/test0.txt".to_string()).unwrap();
+
+    let result = run_rgrep_detailed("regex", &file_text).unwrap();
+    assert_eq!(result.lines, vec!["regex", "no regex", "multiple regex"]);
+    assert_eq!(result.total_lines_scanned, 6);
+    assert_eq!(result.matched_lines, 3);
+    assert!(result.elapsed < std::time::Duration::from_secs(5));
+
+    assert!(run_rgrep_detailed("[", &file_text).is_err());
+}
+
+#[test]
+fn test_funcionamiento_separador_de_registros() {
+    let binding = {
+        vec![
+            "rgrep",
+            "--record-separator",
+            "^\\[",
+            "line 3|detail",
+            "res/records.log",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec![
+            "[10:00] start\ndetail one",
+            "[10:01] error found\n  at line 3"
+        ]
+    );
+}
+
+#[test]
+fn test_funcionamiento_patrones_de_solo_anclas() {
+    let file_text = read_file("res/blank_line.txt".to_string()).unwrap();
+
+    let program_output = run_rgrep("^", &file_text).unwrap();
+    assert_eq!(program_output, vec!["first line", "", "third line"]);
+
+    let program_output = run_rgrep("$", &file_text).unwrap();
+    assert_eq!(program_output, vec!["first line", "", "third line"]);
+
+    let program_output = run_rgrep("^$", &file_text).unwrap();
+    assert_eq!(program_output, vec![""]);
+
+    let mut progress = Vec::new();
+    let program_output = run_rgrep_progress("^", &file_text, '\n', &mut progress).unwrap();
+    assert_eq!(program_output.len(), 3);
+
+    let result = run_rgrep_detailed("$", &file_text).unwrap();
+    assert_eq!(result.total_lines_scanned, 3);
+    assert_eq!(result.matched_lines, 3);
+
+    assert_eq!(count_total_matches("^", &file_text).unwrap(), 3);
+    assert_eq!(matches_per_line("^$", &file_text).unwrap(), vec![(2, 0)]);
+}
+
+#[test]
+fn test_funcionamiento_contar_lineas_invertidas() {
+    let file_text = read_file("res/test0.txt".to_string()).unwrap();
+    let total_lines = file_text.lines().count();
+
+    let binding = { vec!["rgrep", "-c", "regex", "res/test0.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let matching = run_search(&Arguments::parse(args).unwrap()).unwrap();
+
+    let binding = { vec!["rgrep", "-c", "-v", "regex", "res/test0.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let inverted = run_search(&Arguments::parse(args).unwrap()).unwrap();
+
+    let matching_count: usize = matching.lines[0].parse().unwrap();
+    let inverted_count: usize = inverted.lines[0].parse().unwrap();
+    assert_eq!(inverted_count, total_lines - matching_count);
+    assert_eq!(inverted.stats.matching_lines, inverted_count);
+    assert_eq!(inverted.exit_code(), 0);
+}
+
+#[test]
+fn test_funcionamiento_maximo_de_coincidencias_por_archivo() {
+    let binding = {
+        vec![
+            "rgrep",
+            "--max-count-per-file",
+            "2",
+            "regex",
+            "res/samples/first.txt",
+            "res/samples/second.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec![
+            "res/samples/first.txt:regex 1",
+            "res/samples/first.txt:regex 2",
+            "res/samples/second.txt:regex a",
+            "res/samples/second.txt:regex b",
+        ]
+    );
+
+    let binding = {
+        vec![
+            "rgrep",
+            "-c",
+            "--max-count-per-file",
+            "1",
+            "regex",
+            "res/samples/first.txt",
+            "res/samples/second.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec!["res/samples/first.txt:1", "res/samples/second.txt:1"]
+    );
+}
+
+#[test]
+fn test_funcionamiento_maximo_de_coincidencias_por_archivo_binario() {
+    let binding = {
+        vec![
+            "rgrep",
+            "-c",
+            "--max-count-per-file",
+            "1",
+            "abc",
+            "res/binary.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["1"]);
+    assert_eq!(program_output.stats.matching_lines, 1);
+
+    let binding = {
+        vec![
+            "rgrep",
+            "--max-count-per-file",
+            "0",
+            "abc",
+            "res/binary.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert!(program_output.lines.is_empty());
+    assert_eq!(program_output.exit_code(), EXIT_NO_MATCH);
+}
+
+#[test]
+fn test_funcionamiento_texto_fijo_sin_distinguir_mayusculas() {
+    let binding = { vec!["rgrep", "-F", "-i", "abc", "res/ignore_case.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["ABC", "abc"]);
+
+    let binding = { vec!["rgrep", "-F", "-i", "Foo.Bar", "res/ignore_case.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["FOO.BAR"]);
+
+    let binding = { vec!["rgrep", "-F", "-i", "-o", "aBc", "res/ignore_case.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["ABC", "abc"]);
+}
+
+#[test]
+fn test_funcionamiento_tsv_sin_linea_final_ni_coincidencias_vacias() {
+    let binding = { vec!["rgrep", "--tsv", "^", "res/test2.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec![
+            "1\t0\taaa",
+            "2\t0\tee|oo",
+            "3\t0\teo",
+            "4\t0\tqqqq|",
+            "5\t0\t|pppp"
+        ]
+    );
+
+    let binding = { vec!["rgrep", "--tsv", "a*", "res/test2.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines[0], "1\t1\taaa");
+    assert_eq!(program_output.lines[1], "2\t0\tee|oo");
+}
+
+#[test]
+fn test_funcionamiento_sin_distinguir_mayusculas_multilinea_y_tsv() {
+    let binding = {
+        vec![
+            "rgrep",
+            "-i",
+            "--multiline",
+            "abc[[:space:]]ABC",
+            "res/ignore_case.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["ABC\nabc"]);
+
+    let binding = { vec!["rgrep", "-i", "--tsv", "abc", "res/ignore_case.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["1\t1\tABC", "2\t1\tabc"]);
+}
+
+#[test]
+fn test_funcionamiento_clases_de_mayusculas_sin_distinguir_mayusculas() {
+    let binding = { vec!["rgrep", "-i", "^[[:upper:]]+$", "res/ignore_case.txt"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(program_output.lines, vec!["ABC", "abc", "fooXbar", "xyz"]);
+
+    let binding = {
+        vec![
+            "rgrep",
+            "--ignore-case",
+            "-o",
+            "[[:lower:]]+",
+            "res/ignore_case.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec!["ABC", "abc", "FOO", "BAR", "fooXbar", "xyz"]
+    );
+}
+
+#[test]
+fn test_funcionamiento_prefijos_coloreados() {
+    let binding = {
+        vec![
+            "rgrep",
+            "--color=always",
+            "-n",
+            "-H",
+            "regex 2",
+            "res/samples/first.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec!["\x1b[35mres/samples/first.txt\x1b[0m:\x1b[32m2\x1b[0m:\x1b[01;31mregex 2\x1b[0m"]
+    );
+
+    let binding = {
+        vec![
+            "rgrep",
+            "--color=never",
+            "-n",
+            "-H",
+            "regex 2",
+            "res/samples/first.txt",
+        ]
+    };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec!["res/samples/first.txt:2:regex 2"]
+    );
+}
+
+#[test]
+fn test_funcionamiento_lista_de_archivos() {
+    let binding = { vec!["rgrep", "--files-from", "res/file_list.txt", "regex [2b]"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let arguments = Arguments::parse(args).unwrap();
+
+    assert_eq!(
+        arguments.paths,
+        vec!["res/samples/first.txt", "res/samples/second.txt"]
+    );
+
+    let program_output = run_search(&arguments).unwrap();
+    assert_eq!(
+        program_output.lines,
+        vec![
+            "res/samples/first.txt:regex 2",
+            "res/samples/second.txt:regex b"
+        ]
+    );
+
+    let binding = { vec!["rgrep", "--files-from", "res/missing.txt", "regex"] };
+    let args = binding.iter().map(|s| s.to_string());
+    let error = Arguments::parse(args).unwrap_err();
+    assert_eq!(error.message(), ProgramError::InvalidFilePath.message());
+}